@@ -1,14 +1,16 @@
+use proc_macro2::TokenStream;
 use quote::quote;
-use syn::Fields;
+use syn::{Attribute, Error, Fields, Meta, NestedMeta};
 use synstructure::{decl_derive, AddBounds};
 
-decl_derive!([Debug/*, attributes(debug_skip)*/] => derive_debug);
+decl_derive!([Debug, attributes(debug2)] => derive_debug);
 
 // Based on
 // https://github.com/panicbit/custom_debug/blob/master/custom_debug_derive/src/lib.rs
 
 fn derive_debug(mut s: synstructure::Structure) -> proc_macro2::TokenStream {
     s.add_bounds(AddBounds::Generics);
+    s.underscore_const(true);
 
     let variants = s.each_variant(|variant| {
         let name = variant.ast().ident.to_string();
@@ -19,7 +21,11 @@ fn derive_debug(mut s: synstructure::Structure) -> proc_macro2::TokenStream {
         };
 
         let variant_body = variant.bindings().iter().map(|b| {
-            let format = quote! {#b};
+            let format = match int_style(&b.ast().attrs) {
+                Ok(Some(style)) => quote! {&debug2::IntStyled(#b, debug2::IntStyle::#style)},
+                Ok(None) => quote! {#b},
+                Err(e) => return e.to_compile_error(),
+            };
 
             if let Some(ref name) = b.ast().ident.as_ref().map(<_>::to_string) {
                 quote! {
@@ -47,3 +53,34 @@ fn derive_debug(mut s: synstructure::Structure) -> proc_macro2::TokenStream {
         }
     })
 }
+
+/// Finds the `IntStyle` variant set by `#[debug2(hex)]` and friends, if any.
+fn int_style(attrs: &[Attribute]) -> syn::Result<Option<TokenStream>> {
+    let mut style = None;
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("debug2")) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => return Err(Error::new_spanned(meta, "expected `#[debug2(...)]`")),
+        };
+        for nested in list.nested {
+            let ident = match &nested {
+                NestedMeta::Meta(Meta::Path(path)) => path.get_ident(),
+                _ => None,
+            };
+            style = Some(match ident.map(<_>::to_string).as_deref() {
+                Some("decimal") => quote! {Decimal},
+                Some("grouped") => quote! {Grouped},
+                Some("hex") => quote! {Hex},
+                Some("binary") => quote! {Binary},
+                Some("octal") => quote! {Octal},
+                _ => {
+                    return Err(Error::new_spanned(
+                        nested,
+                        "expected one of `decimal`, `grouped`, `hex`, `binary` or `octal`",
+                    ))
+                }
+            });
+        }
+    }
+    Ok(style)
+}
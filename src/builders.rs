@@ -1,17 +1,12 @@
-use crate::{Debug, Formatter};
+use crate::{Align, Debug, Formatter, Sink, TokenKind};
+use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::fmt;
 
-struct Formatted(String);
-
-impl Debug for Formatted {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        f.write_str(&self.0)
-    }
-}
-
 struct PadAdapter<'buf, 'state> {
     buf: &'buf mut (dyn fmt::Write + 'buf),
     state: &'state mut PadAdapterState,
+    indent: &'buf str,
 }
 
 struct PadAdapterState {
@@ -30,10 +25,13 @@ impl<'buf, 'state> PadAdapter<'buf, 'state> {
         slot: &'slot mut Option<Self>,
         state: &'state mut PadAdapterState,
     ) -> Formatter<'slot> {
-        fmt.wrap_buf(move |buf| {
-            *slot = Some(PadAdapter { buf, state });
+        let indent = &fmt.opts.indent;
+        let mut fmt = fmt.wrap_buf(move |buf| {
+            *slot = Some(PadAdapter { buf, state, indent });
             slot.as_mut().unwrap()
-        })
+        });
+        fmt.indent += 1;
+        fmt
     }
 }
 
@@ -41,7 +39,7 @@ impl fmt::Write for PadAdapter<'_, '_> {
     fn write_str(&mut self, mut s: &str) -> fmt::Result {
         while !s.is_empty() {
             if self.state.on_newline {
-                self.buf.write_str("    ")?;
+                self.buf.write_str(self.indent)?;
             }
 
             let split = match s.find('\n') {
@@ -99,34 +97,114 @@ impl fmt::Write for PadAdapter<'_, '_> {
 /// ```
 #[must_use = "must eventually call `finish()` on Debug builders"]
 #[allow(missing_debug_implementations)]
-
 pub struct DebugStruct<'a, 'b: 'a> {
     fmt: &'a mut Formatter<'b>,
     result: fmt::Result,
     has_fields: bool,
+    elided: bool,
+    // The width to pad field names to, see `PrettyOptions::align_keys`.
+    key_width: Option<usize>,
+    // Whether `fmt.depth` has been put back, see `leave_depth`.
+    depth_left: bool,
 }
 
 pub(super) fn debug_struct_new<'a, 'b>(
     fmt: &'a mut Formatter<'b>,
     name: &str,
 ) -> DebugStruct<'a, 'b> {
-    let result = fmt.write_str(name);
+    let result = match fmt.sink() {
+        Some(sink) => sink.begin_struct(name),
+        None => fmt.write_token(TokenKind::Name, name),
+    };
+    let elided = fmt.is_too_deep();
+    record_builder(fmt, if elided { None } else { Some(name) });
+    let key_width = fmt.key_width.take();
+    fmt.depth += 1;
+    fmt.nested = true;
     DebugStruct {
         fmt,
         result,
         has_fields: false,
+        elided,
+        key_width,
+        depth_left: false,
     }
 }
 
 // This is the crux of the problem
 fn write_maybe_short<T: Debug + ?Sized>(val: &T, writer: &mut Formatter) -> fmt::Result {
     // TODO: Early return this if we run out of space
-    let as_str = crate::flatprint_checked(val)?;
-    // TODO: Make this configurable and keep track of current depth.
-    if as_str.len() <= crate::MAX_LEN {
-        writer.buf.write_str(&as_str)
+    if !writer.opts.align_keys {
+        let as_str = crate::flatprint_checked(val, writer.opts, writer.depth, writer.int_style)?;
+        return if crate::text_width(&as_str) <= writer.remaining_on_line() {
+            writer.buf.write_str(&as_str)
+        } else {
+            val.fmt(writer)
+        };
+    }
+
+    let flat = crate::flatprint_entry(val, writer.opts, writer.depth, writer.int_style)?;
+    if crate::text_width(&flat.text) <= writer.remaining_on_line() {
+        writer.buf.write_str(&flat.text)
     } else {
-        val.fmt(writer)
+        writer.key_width = flat.key_width;
+        let result = val.fmt(writer);
+        writer.key_width = None;
+        result
+    }
+}
+
+/// Writes the separator between a key and its value, then the value.
+///
+/// The key is padded to `key_width`, if given, but only if the value fits on
+/// the rest of the line, so a value split over several lines doesn't start
+/// after a gap.
+fn write_key_value(
+    writer: &mut Formatter<'_>,
+    key_width: Option<usize>,
+    value: &dyn Debug,
+) -> fmt::Result {
+    if let Some(width) = key_width {
+        let column = writer.indent * writer.opts.indent_width() + width;
+        let padding = column.saturating_sub(writer.column.get());
+        let flat = crate::flatprint_checked(value, writer.opts, writer.depth, writer.int_style)?;
+        let needed =
+            padding + crate::text_width(&writer.opts.field_separator) + crate::text_width(&flat);
+        if needed <= writer.remaining_on_line() {
+            write_key_separator(writer, padding)?;
+            return writer.buf.write_str(&flat);
+        }
+    }
+    write_key_separator(writer, 0)?;
+    write_maybe_short(value, writer)
+}
+
+/// Writes the separator between a key and its value, padding the key with
+/// `padding` spaces.
+fn write_key_separator(writer: &mut Formatter<'_>, padding: usize) -> fmt::Result {
+    let opts = writer.opts;
+    let padding = " ".repeat(padding);
+    // Keep `:` next to the key, but line up the `=` in `key = value`.
+    if opts.field_separator.starts_with(char::is_whitespace) {
+        writer.write_punct(&padding)?;
+        writer.write_punct(&opts.field_separator)
+    } else {
+        writer.write_punct(&opts.field_separator)?;
+        writer.write_punct(&padding)
+    }
+}
+
+fn write_pretty_end(fmt: &mut Formatter<'_>) -> fmt::Result {
+    fmt.write_punct(if fmt.opts.trailing_comma { ",\n" } else { "\n" })
+}
+
+/// Leaves the level of nesting a builder entered when it was made. This is
+/// done when the builder is finished, rather than when it's dropped, so the
+/// builders don't need `Drop`, which would keep `fmt` borrowed until the end
+/// of the scope.
+fn leave_depth(fmt: &mut Formatter<'_>, left: &mut bool) {
+    if !std::mem::replace(left, true) {
+        fmt.depth -= 1;
     }
 }
 
@@ -163,26 +241,56 @@ impl<'a, 'b: 'a> DebugStruct<'a, 'b> {
     ///     "Bar { bar: 10, another: \"Hello World\", not_existing_field: 1 }",
     /// );
     /// ```
-
     pub fn field(&mut self, name: &str, value: &dyn Debug) -> &mut Self {
         self.result = self.result.and_then(|_| {
-            if self.is_pretty() {
+            if let Some(sink) = self.fmt.sink() {
+                if self.elided {
+                    return Ok(());
+                }
+                sink.field(name)?;
+                value.fmt(self.fmt)?;
+                self.fmt.end_scalar()
+            } else if self.elided {
                 if !self.has_fields {
-                    self.fmt.write_str(" {\n")?;
+                    self.fmt.write_punct(" {")?;
+                    self.fmt.write_punct(self.brace_space())?;
+                    self.fmt.write_punct("..")?;
                 }
+                Ok(())
+            } else if self.is_pretty() {
+                let prefix = if self.has_fields { ",\n" } else { " {\n" };
+                self.fmt.write_punct(prefix)?;
                 let mut slot = None;
                 let mut state = Default::default();
-                let mut writer = PadAdapter::wrap(&mut self.fmt, &mut slot, &mut state);
-                writer.write_str(name)?;
-                writer.write_str(": ")?;
-                write_maybe_short(value, &mut writer)?;
-                writer.write_str(",\n")
+                let mut writer = PadAdapter::wrap(self.fmt, &mut slot, &mut state);
+                writer.write_token(TokenKind::Field, name)?;
+                write_key_value(&mut writer, self.key_width, value)
             } else {
-                let prefix = if self.has_fields { ", " } else { " { " };
-                self.fmt.write_str(prefix)?;
-                self.fmt.write_str(name)?;
-                self.fmt.write_str(": ")?;
-                value.fmt(self.fmt)
+                if self.has_fields {
+                    self.fmt.write_punct(", ")?;
+                } else {
+                    self.fmt.write_punct(" {")?;
+                    self.fmt.write_punct(self.brace_space())?;
+                }
+                self.fmt.write_token(TokenKind::Field, name)?;
+                let opts = self.fmt.opts;
+                self.fmt.write_punct(&opts.field_separator)?;
+                match self.fmt.row {
+                    Some(row) if row.borrow().is_recording(self.fmt) => {
+                        let text = crate::flatprint_checked(
+                            value,
+                            opts,
+                            self.fmt.depth,
+                            self.fmt.int_style,
+                        )?;
+                        self.fmt.write_str(&text)?;
+                        let mut row = row.borrow_mut();
+                        row.record_key(crate::text_width(name));
+                        row.fields.push((name.to_owned(), text));
+                        Ok(())
+                    }
+                    _ => value.fmt(self.fmt),
+                }
             }
         });
 
@@ -220,23 +328,43 @@ impl<'a, 'b: 'a> DebugStruct<'a, 'b> {
     ///     "Bar { bar: 10, .. }",
     /// );
     /// ```
-
     pub fn finish_non_exhaustive(&mut self) -> fmt::Result {
+        if let Some(row) = self.fmt.row {
+            let mut row = row.borrow_mut();
+            if row.is_recording(self.fmt) {
+                row.is_struct = false;
+            }
+        }
         self.result = self.result.and_then(|_| {
-            if self.has_fields {
+            let space = self.brace_space();
+            if let Some(sink) = self.fmt.sink() {
+                sink.non_exhaustive()?;
+                sink.end()
+            } else if self.elided && self.has_fields {
+                self.fmt.write_punct(space)?;
+                self.fmt.write_punct("}")
+            } else if self.has_fields {
                 if self.is_pretty() {
+                    self.fmt.write_punct(",\n")?;
                     let mut slot = None;
                     let mut state = Default::default();
-                    let mut writer = PadAdapter::wrap(&mut self.fmt, &mut slot, &mut state);
-                    writer.write_str("..\n")?;
-                    self.fmt.write_str("}")
+                    let mut writer = PadAdapter::wrap(self.fmt, &mut slot, &mut state);
+                    writer.write_punct("..\n")?;
+                    self.fmt.write_punct("}")
                 } else {
-                    self.fmt.write_str(", .. }")
+                    self.fmt.write_punct(", ..")?;
+                    self.fmt.write_punct(space)?;
+                    self.fmt.write_punct("}")
                 }
             } else {
-                self.fmt.write_str(" { .. }")
+                self.fmt.write_punct(" {")?;
+                self.fmt.write_punct(space)?;
+                self.fmt.write_punct("..")?;
+                self.fmt.write_punct(space)?;
+                self.fmt.write_punct("}")
             }
         });
+        leave_depth(self.fmt, &mut self.depth_left);
         self.result
     }
 
@@ -271,20 +399,37 @@ impl<'a, 'b: 'a> DebugStruct<'a, 'b> {
     ///     "Bar { bar: 10, baz: \"Hello World\" }",
     /// );
     /// ```
-
     pub fn finish(&mut self) -> fmt::Result {
-        if self.has_fields {
+        let elided = self.elided && self.has_fields;
+        if let Some(sink) = self.fmt.sink() {
             self.result = self.result.and_then(|_| {
-                if self.is_pretty() {
-                    self.fmt.write_str("}")
+                if elided {
+                    sink.non_exhaustive()?;
+                }
+                sink.end()
+            });
+        } else if self.has_fields {
+            self.result = self.result.and_then(|_| {
+                if self.is_pretty() && !self.elided {
+                    write_pretty_end(self.fmt)?;
                 } else {
-                    self.fmt.write_str(" }")
+                    self.fmt.write_punct(self.brace_space())?;
                 }
+                self.fmt.write_punct("}")
             });
         }
+        leave_depth(self.fmt, &mut self.depth_left);
         self.result
     }
 
+    fn brace_space(&self) -> &'static str {
+        if self.fmt.opts.space_inside_braces {
+            " "
+        } else {
+            ""
+        }
+    }
+
     fn is_pretty(&self) -> bool {
         self.fmt.is_pretty()
     }
@@ -321,24 +466,36 @@ impl<'a, 'b: 'a> DebugStruct<'a, 'b> {
 /// ```
 #[must_use = "must eventually call `finish()` on Debug builders"]
 #[allow(missing_debug_implementations)]
-
 pub struct DebugTuple<'a, 'b: 'a> {
     fmt: &'a mut Formatter<'b>,
     result: fmt::Result,
     fields: usize,
     empty_name: bool,
+    elided: bool,
+    // Whether `fmt.depth` has been put back, see `leave_depth`.
+    depth_left: bool,
 }
 
 pub(super) fn debug_tuple_new<'a, 'b>(
     fmt: &'a mut Formatter<'b>,
     name: &str,
 ) -> DebugTuple<'a, 'b> {
-    let result = fmt.write_str(name);
+    let result = match fmt.sink() {
+        Some(sink) => sink.begin_tuple(name),
+        None => fmt.write_token(TokenKind::Name, name),
+    };
+    let elided = fmt.is_too_deep();
+    record_builder(fmt, None);
+    fmt.key_width = None;
+    fmt.depth += 1;
+    fmt.nested = true;
     DebugTuple {
         fmt,
         result,
         fields: 0,
         empty_name: name.is_empty(),
+        elided,
+        depth_left: false,
     }
 }
 
@@ -367,21 +524,29 @@ impl<'a, 'b: 'a> DebugTuple<'a, 'b> {
     ///     "Foo(10, \"Hello World\")",
     /// );
     /// ```
-
     pub fn field(&mut self, value: &dyn Debug) -> &mut Self {
         self.result = self.result.and_then(|_| {
-            if self.is_pretty() {
+            if self.fmt.sink.is_some() {
+                if self.elided {
+                    return Ok(());
+                }
+                value.fmt(self.fmt)?;
+                self.fmt.end_scalar()
+            } else if self.elided {
                 if self.fields == 0 {
-                    self.fmt.write_str("(\n")?;
+                    self.fmt.write_punct("(..")?;
                 }
+                Ok(())
+            } else if self.is_pretty() {
+                let prefix = if self.fields == 0 { "(\n" } else { ",\n" };
+                self.fmt.write_punct(prefix)?;
                 let mut slot = None;
                 let mut state = Default::default();
-                let mut writer = PadAdapter::wrap(&mut self.fmt, &mut slot, &mut state);
-                write_maybe_short(&value, &mut writer)?;
-                writer.write_str(",\n")
+                let mut writer = PadAdapter::wrap(self.fmt, &mut slot, &mut state);
+                write_maybe_short(&value, &mut writer)
             } else {
                 let prefix = if self.fields == 0 { "(" } else { ", " };
-                self.fmt.write_str(prefix)?;
+                self.fmt.write_punct(prefix)?;
                 value.fmt(self.fmt)
             }
         });
@@ -415,16 +580,33 @@ impl<'a, 'b: 'a> DebugTuple<'a, 'b> {
     ///     "Foo(10, \"Hello World\")",
     /// );
     /// ```
-
     pub fn finish(&mut self) -> fmt::Result {
-        if self.fields > 0 {
+        let elided = self.elided && self.fields > 0;
+        if let Some(sink) = self.fmt.sink() {
+            self.result = self.result.and_then(|_| {
+                if elided {
+                    sink.non_exhaustive()?;
+                }
+                sink.end()
+            });
+        } else if self.fields > 0 {
             self.result = self.result.and_then(|_| {
-                if self.fields == 1 && self.empty_name && !self.is_pretty() {
-                    self.fmt.write_str(",")?;
+                // A 1-tuple always needs its comma, so it isn't confused
+                // with a parenthesized value.
+                let one_tuple = self.fields == 1 && self.empty_name;
+                if self.is_pretty() && !self.elided {
+                    if one_tuple {
+                        self.fmt.write_punct(",\n")?;
+                    } else {
+                        write_pretty_end(self.fmt)?;
+                    }
+                } else if one_tuple && !self.elided {
+                    self.fmt.write_punct(",")?;
                 }
-                self.fmt.write_str(")")
+                self.fmt.write_punct(")")
             });
         }
+        leave_depth(self.fmt, &mut self.depth_left);
         self.result
     }
 
@@ -437,23 +619,77 @@ struct DebugInner<'a, 'b: 'a> {
     fmt: &'a mut Formatter<'b>,
     result: fmt::Result,
     has_fields: bool,
+    limit: Limit,
+    align: Align,
+    // Entries held back until we know how to lay them out.
+    pending: Option<Pending>,
+    // Whether to sort entries given to `entries`.
+    sorted: bool,
+    // Whether `fmt.depth` has been put back, see `leave_depth`.
+    depth_left: bool,
+}
+
+/// Entries of a list in pretty mode that might not get a line each.
+enum Pending {
+    Start,
+    /// Every entry so far is a leaf. See `PrettyOptions::fill`.
+    Leaves(Vec<String>),
+    /// Every entry so far is a struct with the same fields. See
+    /// `PrettyOptions::align`.
+    Rows(Vec<(String, Row)>),
 }
 
 impl<'a, 'b: 'a> DebugInner<'a, 'b> {
+    fn new(fmt: &'a mut Formatter<'b>, result: fmt::Result) -> Self {
+        let limit = Limit::new(fmt);
+        let align = fmt.opts.align;
+        record_builder(fmt, None);
+        fmt.key_width = None;
+        fmt.depth += 1;
+        fmt.nested = true;
+        let mut inner = DebugInner {
+            fmt,
+            result,
+            has_fields: false,
+            limit,
+            align,
+            pending: None,
+            sorted: false,
+            depth_left: false,
+        };
+        inner.set_align(align);
+        inner
+    }
+
+    fn set_align(&mut self, align: Align) {
+        self.align = align;
+        let holds = self.fmt.opts.fill || align != Align::Off;
+        if self.is_pretty() && !self.has_fields && holds {
+            self.pending = Some(Pending::Start);
+        }
+    }
+
     fn entry(&mut self, entry: &dyn Debug) {
+        if self.limit.skip() {
+            return;
+        }
+
         self.result = self.result.and_then(|_| {
-            if self.is_pretty() {
-                if !self.has_fields {
-                    self.fmt.write_str("\n")?;
-                }
+            if self.fmt.sink.is_some() {
+                entry.fmt(self.fmt)?;
+                self.fmt.end_scalar()
+            } else if self.hold(entry)? {
+                Ok(())
+            } else if self.is_pretty() {
+                let prefix = if self.has_fields { ",\n" } else { "\n" };
+                self.fmt.write_punct(prefix)?;
                 let mut slot = None;
                 let mut state = Default::default();
-                let mut writer = PadAdapter::wrap(&mut self.fmt, &mut slot, &mut state);
-                write_maybe_short(&entry, &mut writer)?;
-                writer.write_str(",\n")
+                let mut writer = PadAdapter::wrap(self.fmt, &mut slot, &mut state);
+                write_maybe_short(&entry, &mut writer)
             } else {
                 if self.has_fields {
-                    self.fmt.write_str(", ")?
+                    self.fmt.write_punct(", ")?
                 }
                 entry.fmt(self.fmt)
             }
@@ -462,11 +698,562 @@ impl<'a, 'b: 'a> DebugInner<'a, 'b> {
         self.has_fields = true;
     }
 
+    /// Holds back an entry if it's like all the ones before it, returning
+    /// `false` if it should be written as usual.
+    fn hold(&mut self, entry: &dyn Debug) -> Result<bool, fmt::Error> {
+        let pending = match self.pending.take() {
+            Some(pending) => pending,
+            None => return Ok(false),
+        };
+
+        let opts = self.fmt.opts;
+        let flat = crate::flatprint_entry(entry, opts, self.fmt.depth, self.fmt.int_style)?;
+        let fits = crate::text_width(&flat.text) <= fill_width(self.fmt);
+        let aligned = self.align != Align::Off;
+
+        self.pending = match (pending, flat.row) {
+            (Pending::Start, _) if fits && flat.leaf && opts.fill => {
+                Some(Pending::Leaves(vec![flat.text]))
+            }
+            (Pending::Start, Some(row)) if fits && aligned => {
+                Some(Pending::Rows(vec![(flat.text, row)]))
+            }
+            (Pending::Leaves(mut leaves), _) if fits && flat.leaf => {
+                leaves.push(flat.text);
+                Some(Pending::Leaves(leaves))
+            }
+            (Pending::Rows(mut rows), Some(row))
+                if fits && row.lines_up_with(&rows[0].1, self.align) =>
+            {
+                rows.push((flat.text, row));
+                Some(Pending::Rows(rows))
+            }
+            // Not every entry is alike, so go back to one entry per line.
+            (pending, _) => {
+                self.write_lines(pending.into_lines())?;
+                return Ok(false);
+            }
+        };
+        Ok(true)
+    }
+
+    /// Writes already formatted entries, one per line.
+    fn write_lines(&mut self, lines: Vec<String>) -> fmt::Result {
+        for (i, line) in lines.iter().enumerate() {
+            self.fmt.write_punct(if i == 0 { "\n" } else { ",\n" })?;
+            let mut slot = None;
+            let mut state = Default::default();
+            let mut writer = PadAdapter::wrap(self.fmt, &mut slot, &mut state);
+            writer.write_str(line)?;
+        }
+        Ok(())
+    }
+
+    fn entries<D, I>(&mut self, entries: I)
+    where
+        D: Debug,
+        I: IntoIterator<Item = D>,
+    {
+        let mut entries = entries.into_iter();
+        if self.sorted && !self.limit.elided {
+            let most = self.limit.most_sorted();
+            match sort_by_text(self.fmt, entries.by_ref().take(most), |entry| entry) {
+                Ok(sorted) => self.push_entries(sorted),
+                Err(e) => self.result = Err(e),
+            }
+            self.limit.skip_rest(entries);
+        } else {
+            self.push_entries(entries);
+        }
+    }
+
+    fn push_entries<D, I>(&mut self, entries: I)
+    where
+        D: Debug,
+        I: IntoIterator<Item = D>,
+    {
+        let mut entries = entries.into_iter();
+        while !self.limit.is_full() {
+            match entries.next() {
+                Some(entry) => self.entry(&entry),
+                None => return,
+            }
+        }
+        self.limit.skip_rest(entries);
+    }
+
+    fn finish(&mut self, close: &str) -> fmt::Result {
+        let result = self.result.and_then(|_| {
+            if let Some(sink) = self.fmt.sink() {
+                return self.limit.sink_end(sink);
+            }
+            let mut commas = true;
+            match self.pending.take() {
+                Some(Pending::Leaves(leaves)) => self.write_filled(&leaves)?,
+                Some(Pending::Rows(rows)) => {
+                    let width = fill_width(self.fmt);
+                    let lines = match self.align {
+                        Align::Table => table_lines(&rows, self.fmt.opts),
+                        _ => aligned_lines(&rows, self.fmt.opts),
+                    };
+                    if lines.iter().all(|line| crate::text_width(line) <= width) {
+                        commas = self.align != Align::Table;
+                        self.fmt.write_punct("\n")?;
+                        let mut slot = None;
+                        let mut state = Default::default();
+                        let mut writer = PadAdapter::wrap(self.fmt, &mut slot, &mut state);
+                        let sep = if commas { ",\n" } else { "\n" };
+                        writer.write_str(&lines.join(sep))?;
+                    } else {
+                        self.write_lines(Pending::Rows(rows).into_lines())?;
+                    }
+                }
+                Some(Pending::Start) | None => {}
+            }
+            self.limit.write_end(self.fmt, self.has_fields, commas)?;
+            self.fmt.write_punct(close)
+        });
+        leave_depth(self.fmt, &mut self.depth_left);
+        result
+    }
+
+    /// Writes leaves packed as many to a line as fit.
+    fn write_filled(&mut self, leaves: &[String]) -> fmt::Result {
+        self.fmt.write_punct("\n")?;
+        let width = fill_width(self.fmt);
+        let mut slot = None;
+        let mut state = Default::default();
+        let mut writer = PadAdapter::wrap(self.fmt, &mut slot, &mut state);
+        let mut line_width = 0;
+        for (i, text) in leaves.iter().enumerate() {
+            let text_width = crate::text_width(text);
+            if i > 0 {
+                if line_width + ", ".len() + text_width <= width {
+                    writer.write_punct(", ")?;
+                    line_width += ", ".len();
+                } else {
+                    writer.write_punct(",\n")?;
+                    line_width = 0;
+                }
+            }
+            writer.write_str(text)?;
+            line_width += text_width;
+        }
+        Ok(())
+    }
+
     fn is_pretty(&self) -> bool {
         self.fmt.is_pretty()
     }
 }
 
+/// Sorts entries by how their keys are written on one line.
+fn sort_by_text<T, K, I>(
+    fmt: &Formatter<'_>,
+    entries: I,
+    key: impl Fn(&T) -> &K,
+) -> Result<Vec<T>, fmt::Error>
+where
+    K: Debug,
+    I: IntoIterator<Item = T>,
+{
+    let mut keyed = entries
+        .into_iter()
+        .map(|entry| {
+            let text = crate::flatprint_checked(key(&entry), fmt.opts, fmt.depth, fmt.int_style)?;
+            // Colors shouldn't change the order.
+            Ok((crate::color::strip_escapes(&text).into_owned(), entry))
+        })
+        .collect::<Result<Vec<_>, fmt::Error>>()?;
+    keyed.sort_by(|(a, _), (b, _)| natural_cmp(a, b));
+    Ok(keyed.into_iter().map(|(_, entry)| entry).collect())
+}
+
+/// Compares text, treating runs of digits as numbers, so `9` comes before `10`
+/// and `-10` before `-5`.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    fn split_digits(s: &str) -> (&str, &str) {
+        s.split_at(s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len()))
+    }
+
+    // The digits of a number at the start of `s`, and whether it's negative.
+    // A `-` straight after a word joins it to the number, like in `item-5`.
+    fn number(s: &str, after_word: bool) -> Option<(bool, &str)> {
+        match s.strip_prefix('-') {
+            Some(digits) if !after_word && digits.starts_with(|c: char| c.is_ascii_digit()) => {
+                Some((true, digits))
+            }
+            _ if s.starts_with(|c: char| c.is_ascii_digit()) => Some((false, s)),
+            _ => None,
+        }
+    }
+
+    let (mut rest_a, mut rest_b) = (a, b);
+    let mut after_word = false;
+    loop {
+        let (x, y) = match (rest_a.chars().next(), rest_b.chars().next()) {
+            (Some(x), Some(y)) => (x, y),
+            _ => return rest_a.len().cmp(&rest_b.len()).then_with(|| a.cmp(b)),
+        };
+        if let (Some((neg_a, digits_a)), Some((neg_b, digits_b))) =
+            (number(rest_a, after_word), number(rest_b, after_word))
+        {
+            let (num_a, tail_a) = split_digits(digits_a);
+            let (num_b, tail_b) = split_digits(digits_b);
+            let num_a = num_a.trim_start_matches('0');
+            let num_b = num_b.trim_start_matches('0');
+            let ord = num_a.len().cmp(&num_b.len()).then_with(|| num_a.cmp(num_b));
+            let ord = match (neg_a, neg_b) {
+                (false, false) => ord,
+                (true, true) => ord.reverse(),
+                (true, false) => Ordering::Less,
+                (false, true) => Ordering::Greater,
+            };
+            if ord != Ordering::Equal {
+                return ord;
+            }
+            rest_a = tail_a;
+            rest_b = tail_b;
+            after_word = true;
+        } else if x != y {
+            return x.cmp(&y);
+        } else {
+            rest_a = &rest_a[x.len_utf8()..];
+            rest_b = &rest_b[y.len_utf8()..];
+            after_word = x.is_alphanumeric() || x == '_';
+        }
+    }
+}
+
+impl Pending {
+    fn into_lines(self) -> Vec<String> {
+        match self {
+            Pending::Start => Vec::new(),
+            Pending::Leaves(leaves) => leaves,
+            Pending::Rows(rows) => rows.into_iter().map(|(text, _)| text).collect(),
+        }
+    }
+}
+
+/// The fields of a struct printed on one line, recorded so they can be lined
+/// up with other entries. See `PrettyOptions::align`.
+pub(crate) struct Row {
+    // The depth the struct's builder is created at.
+    depth: usize,
+    name: Option<String>,
+    fields: Vec<(String, String)>,
+    // Whether this is a single struct, with all its fields shown.
+    is_struct: bool,
+    // The width of the widest field name or map key.
+    pub(crate) key_width: Option<usize>,
+}
+
+impl Row {
+    pub(crate) fn new(depth: usize) -> Self {
+        Row {
+            depth,
+            name: None,
+            fields: Vec::new(),
+            is_struct: true,
+            key_width: None,
+        }
+    }
+
+    pub(crate) fn finish(self) -> Option<Self> {
+        if self.is_struct && self.name.is_some() {
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    /// Whether this row can be laid out along with `other`. They need the
+    /// same fields, and in a table, which doesn't show names, the same name.
+    fn lines_up_with(&self, other: &Row, align: Align) -> bool {
+        (align != Align::Table || self.name == other.name)
+            && self.fields.len() == other.fields.len()
+            && self
+                .fields
+                .iter()
+                .zip(&other.fields)
+                .all(|((a, _), (b, _))| a == b)
+    }
+
+    fn record_key(&mut self, width: usize) {
+        self.key_width = Some(self.key_width.map_or(width, |max| max.max(width)));
+    }
+
+    /// Whether `fmt` is writing the fields of the recorded struct.
+    fn is_recording(&self, fmt: &Formatter<'_>) -> bool {
+        self.depth + 1 == fmt.depth
+    }
+}
+
+/// Records a new builder in the row being recorded, if any. Only a struct
+/// that isn't elided gets a `name`.
+fn record_builder(fmt: &Formatter<'_>, name: Option<&str>) {
+    if let Some(row) = fmt.row {
+        let mut row = row.borrow_mut();
+        if row.depth != fmt.depth {
+            return;
+        }
+        match name {
+            Some(name) if row.name.is_none() => row.name = Some(name.to_owned()),
+            _ => row.is_struct = false,
+        }
+    }
+}
+
+/// Lays out structs so their fields start in the same columns.
+fn aligned_lines(rows: &[(String, Row)], opts: &crate::PrettyOptions) -> Vec<String> {
+    let space = if opts.space_inside_braces { " " } else { "" };
+    let cells: Vec<(Cow<'_, str>, Vec<String>)> = rows
+        .iter()
+        .map(|(_, row)| {
+            let name = opts.paint(TokenKind::Name, row.name.as_deref().unwrap_or_default());
+            let fields = row.fields.iter().enumerate().map(|(i, (field, value))| {
+                let comma = if i + 1 < row.fields.len() { "," } else { "" };
+                format!(
+                    "{}{}{}{}",
+                    opts.paint(TokenKind::Field, field),
+                    opts.paint(TokenKind::Punct, &opts.field_separator),
+                    value,
+                    opts.paint(TokenKind::Punct, comma)
+                )
+            });
+            (name, fields.collect())
+        })
+        .collect();
+    let name_width = column_width(cells.iter().map(|(name, _)| &**name));
+    let widths: Vec<usize> = (0..rows[0].1.fields.len())
+        .map(|i| column_width(cells.iter().map(|(_, fields)| fields[i].as_str())))
+        .collect();
+
+    cells
+        .iter()
+        .map(|(name, fields)| {
+            let mut line = pad(name, name_width);
+            line.push(' ');
+            line.push_str(&opts.paint(TokenKind::Punct, "{"));
+            line.push_str(space);
+            let last = fields.len().saturating_sub(1);
+            for (i, field) in fields.iter().enumerate() {
+                if i < last {
+                    line.push_str(&pad(field, widths[i]));
+                    line.push(' ');
+                } else {
+                    line.push_str(field);
+                }
+            }
+            line.push_str(space);
+            line.push_str(&opts.paint(TokenKind::Punct, "}"));
+            line
+        })
+        .collect()
+}
+
+/// Lays out the fields of structs as a table, with the field names as the
+/// header.
+fn table_lines(rows: &[(String, Row)], opts: &crate::PrettyOptions) -> Vec<String> {
+    let header = &rows[0].1.fields;
+    let widths: Vec<usize> = header
+        .iter()
+        .enumerate()
+        .map(|(i, (name, _))| {
+            let values = rows.iter().map(|(_, row)| row.fields[i].1.as_str());
+            column_width(std::iter::once(name.as_str()).chain(values))
+        })
+        .collect();
+    let line = |cells: Vec<&str>| {
+        let last = cells.len().saturating_sub(1);
+        let mut line = String::new();
+        for (i, cell) in cells.iter().enumerate() {
+            if i < last {
+                line.push_str(&pad(cell, widths[i]));
+                line.push_str(&opts.paint(TokenKind::Punct, " | "));
+            } else {
+                line.push_str(cell);
+            }
+        }
+        line
+    };
+
+    let names: Vec<_> = header
+        .iter()
+        .map(|(name, _)| opts.paint(TokenKind::Field, name))
+        .collect();
+    let mut lines = vec![line(names.iter().map(|name| &**name).collect())];
+    let rule: Vec<String> = widths.iter().map(|&width| "-".repeat(width)).collect();
+    lines.push(opts.paint(TokenKind::Punct, &rule.join("-+-")).into_owned());
+    for (_, row) in rows {
+        lines.push(line(
+            row.fields.iter().map(|(_, value)| value.as_str()).collect(),
+        ));
+    }
+    lines
+}
+
+fn column_width<'s>(cells: impl Iterator<Item = &'s str>) -> usize {
+    cells.map(crate::text_width).max().unwrap_or(0)
+}
+
+/// Pads `s` with spaces to `width` columns.
+fn pad(s: &str, width: usize) -> String {
+    let padding = width.saturating_sub(crate::text_width(s));
+    format!("{}{}", s, " ".repeat(padding))
+}
+
+/// The width available to an entry on its own line, leaving room for the
+/// comma after it.
+fn fill_width(fmt: &Formatter<'_>) -> usize {
+    fmt.remaining_width()
+        .saturating_sub(fmt.opts.indent_width() + ",".len())
+}
+
+/// How many entries that aren't shown are counted, when the iterator doesn't
+/// know how many it has left, and how many are sorted when they aren't all
+/// shown.
+const MAX_COUNTED: usize = 10_000;
+
+/// Tracks how many entries a builder can show, and how many it didn't.
+struct Limit {
+    max: Option<usize>,
+    shown: usize,
+    skipped: usize,
+    // There were too many entries left out to count, so `skipped` is only a
+    // lower bound.
+    uncounted: bool,
+    // Too deep to show anything, see `PrettyOptions::max_depth`
+    elided: bool,
+}
+
+impl Limit {
+    fn new(fmt: &Formatter<'_>) -> Self {
+        let elided = fmt.is_too_deep();
+        Limit {
+            max: if elided { Some(0) } else { fmt.opts.max_items },
+            shown: 0,
+            skipped: 0,
+            uncounted: false,
+            elided,
+        }
+    }
+
+    /// Sets how many entries are shown, unless nothing is shown because
+    /// it's too deep.
+    fn set_max(&mut self, max: usize) {
+        if !self.elided {
+            self.max = Some(max);
+        }
+    }
+
+    /// Records `count` entries that were left out elsewhere, as a
+    /// [`Value`](crate::Value) does.
+    fn hide(&mut self, count: usize, at_least: bool) {
+        self.skipped += count;
+        self.uncounted |= at_least;
+    }
+
+    /// Leaves out the contents, as if it were too deep to show them.
+    fn elide(&mut self) {
+        self.elided = true;
+        self.max = Some(0);
+        self.skipped = self.skipped.max(1);
+    }
+
+    fn is_full(&self) -> bool {
+        self.max.is_some_and(|max| self.shown >= max)
+    }
+
+    /// How many entries to sort. When they won't all be shown, only the
+    /// first `MAX_COUNTED` are, so endless iterators still finish.
+    fn most_sorted(&self) -> usize {
+        match self.max {
+            Some(_) => MAX_COUNTED,
+            None => usize::MAX,
+        }
+    }
+
+    /// Records a new entry, returning `true` if it should not be shown.
+    fn skip(&mut self) -> bool {
+        if self.is_full() {
+            self.skipped += 1;
+            true
+        } else {
+            self.shown += 1;
+            false
+        }
+    }
+
+    fn skip_rest<I: Iterator>(&mut self, mut rest: I) {
+        if self.elided {
+            // We only need to know if there's anything there.
+            self.skipped += rest.next().is_some() as usize;
+            return;
+        }
+
+        self.skipped += match rest.size_hint() {
+            (lower, Some(upper)) if lower == upper => lower,
+            _ => {
+                // Counting is bounded, so endless iterators still finish.
+                let most = MAX_COUNTED.saturating_sub(self.skipped);
+                let counted = rest.take(most + 1).count();
+                self.uncounted |= counted > most;
+                counted.min(most)
+            }
+        };
+    }
+
+    /// Tells `sink` about any entries that weren't shown, and ends the value.
+    fn sink_end(&self, sink: &mut dyn Sink) -> fmt::Result {
+        if self.elided && self.skipped > 0 {
+            sink.non_exhaustive()?;
+        } else if self.skipped > 0 {
+            sink.more(self.skipped, self.uncounted)?;
+        }
+        sink.end()
+    }
+
+    /// Writes what comes after the last entry shown: the marker for any
+    /// entries that weren't, and the end of the last line in pretty mode.
+    /// Without `commas`, lines in pretty mode aren't separated by commas.
+    fn write_end(&self, fmt: &mut Formatter<'_>, has_fields: bool, commas: bool) -> fmt::Result {
+        if self.skipped == 0 {
+            if has_fields && fmt.is_pretty() {
+                if commas {
+                    write_pretty_end(fmt)?;
+                } else {
+                    fmt.write_punct("\n")?;
+                }
+            }
+            return Ok(());
+        }
+
+        if self.elided {
+            return fmt.write_punct("..");
+        }
+
+        let marker = format!(
+            "... {}{} more",
+            crate::group_digits(self.skipped),
+            if self.uncounted { "+" } else { "" }
+        );
+        if fmt.is_pretty() {
+            fmt.write_punct(if has_fields && commas { ",\n" } else { "\n" })?;
+            let mut slot = None;
+            let mut state = Default::default();
+            let mut writer = PadAdapter::wrap(fmt, &mut slot, &mut state);
+            writer.write_punct(&marker)?;
+            writer.write_punct("\n")
+        } else {
+            if has_fields {
+                fmt.write_punct(", ")?;
+            }
+            fmt.write_punct(&marker)
+        }
+    }
+}
+
 /// A struct to help with [`Debug`](Debug) implementations.
 ///
 /// This is useful when you wish to output a formatted set of items as a part
@@ -492,23 +1279,94 @@ impl<'a, 'b: 'a> DebugInner<'a, 'b> {
 /// ```
 #[must_use = "must eventually call `finish()` on Debug builders"]
 #[allow(missing_debug_implementations)]
-
 pub struct DebugSet<'a, 'b: 'a> {
     inner: DebugInner<'a, 'b>,
 }
 
 pub(super) fn debug_set_new<'a, 'b>(fmt: &'a mut Formatter<'b>) -> DebugSet<'a, 'b> {
-    let result = fmt.write_str("{");
+    let result = match fmt.sink() {
+        Some(sink) => sink.begin_set(),
+        None => fmt.write_punct("{"),
+    };
     DebugSet {
-        inner: DebugInner {
-            fmt,
-            result,
-            has_fields: false,
-        },
+        inner: DebugInner::new(fmt, result),
     }
 }
 
 impl<'a, 'b: 'a> DebugSet<'a, 'b> {
+    /// Limits the number of entries shown.
+    ///
+    /// Entries past the limit aren't formatted, and are replaced by a count of
+    /// how many were left out. This overrides
+    /// [`PrettyOptions::max_items`](crate::PrettyOptions::max_items).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use debug2::{pprint, Debug, Formatter};
+    /// use std::fmt;
+    ///
+    /// struct Foo(Vec<i32>);
+    ///
+    /// impl Debug for Foo {
+    ///     fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+    ///         fmt.debug_set().limit(3).entries(self.0.iter()).finish()
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(pprint(Foo((0..10_000).collect())), "{0, 1, 2, ... 9_997 more}");
+    /// ```
+    pub fn limit(&mut self, max: usize) -> &mut Self {
+        self.inner.limit.set_max(max);
+        self
+    }
+
+    /// Adds `count` entries that were left out, like [`limit`](Self::limit)
+    /// does, with `at_least` if there may be more.
+    pub(crate) fn hidden(&mut self, count: usize, at_least: bool) -> &mut Self {
+        self.inner.limit.hide(count, at_least);
+        self
+    }
+
+    /// Shows `..` in place of the entries, as when it's too deep to show them.
+    pub(crate) fn elided(&mut self) -> &mut Self {
+        self.inner.limit.elide();
+        self
+    }
+
+    /// Sorts the entries given to [`entries`](Self::entries), so the output
+    /// doesn't depend on the order they're stored in.
+    ///
+    /// Entries are ordered by how they're written on one line, with numbers
+    /// compared by value. Entries added one at a time with
+    /// [`entry`](Self::entry) are shown in the order they're added. If there
+    /// are more than the [`limit`](Self::limit), only the first 10,000 are
+    /// sorted, so an endless iterator still finishes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use debug2::{pprint, Debug, Formatter};
+    /// use std::fmt;
+    ///
+    /// struct Foo(Vec<&'static str>);
+    ///
+    /// impl Debug for Foo {
+    ///     fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+    ///         fmt.debug_set().sorted().entries(&self.0).finish()
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(
+    ///     pprint(Foo(vec!["item10", "item9", "apple"])),
+    ///     "{\"apple\", \"item9\", \"item10\"}",
+    /// );
+    /// ```
+    pub fn sorted(&mut self) -> &mut Self {
+        self.inner.sorted = true;
+        self
+    }
+
     /// Adds a new entry to the set output.
     ///
     /// # Examples
@@ -533,7 +1391,6 @@ impl<'a, 'b: 'a> DebugSet<'a, 'b> {
     ///     "{[10, 11], [12, 13]}",
     /// );
     /// ```
-
     pub fn entry(&mut self, entry: &dyn Debug) -> &mut Self {
         self.inner.entry(entry);
         self
@@ -560,15 +1417,12 @@ impl<'a, 'b: 'a> DebugSet<'a, 'b> {
     ///
     /// assert_eq!(pprint(Foo(vec![10, 11], vec![12, 13])), "{10, 11, 12, 13}",);
     /// ```
-
     pub fn entries<D, I>(&mut self, entries: I) -> &mut Self
     where
         D: Debug,
         I: IntoIterator<Item = D>,
     {
-        for entry in entries {
-            self.entry(&entry);
-        }
+        self.inner.entries(entries);
         self
     }
 
@@ -590,11 +1444,8 @@ impl<'a, 'b: 'a> DebugSet<'a, 'b> {
     ///
     /// assert_eq!(pprint(Foo(vec![10, 11])), "{10, 11}",);
     /// ```
-
     pub fn finish(&mut self) -> fmt::Result {
-        self.inner
-            .result
-            .and_then(|_| self.inner.fmt.write_str("}"))
+        self.inner.finish("}")
     }
 }
 
@@ -628,17 +1479,148 @@ pub struct DebugList<'a, 'b: 'a> {
 }
 
 pub(super) fn debug_list_new<'a, 'b>(fmt: &'a mut Formatter<'b>) -> DebugList<'a, 'b> {
-    let result = fmt.write_str("[");
+    let result = match fmt.sink() {
+        Some(sink) => sink.begin_list(),
+        None => fmt.write_punct("["),
+    };
     DebugList {
-        inner: DebugInner {
-            fmt,
-            result,
-            has_fields: false,
-        },
+        inner: DebugInner::new(fmt, result),
     }
 }
 
 impl<'a, 'b: 'a> DebugList<'a, 'b> {
+    /// Limits the number of entries shown.
+    ///
+    /// Entries past the limit aren't formatted, and are replaced by a count of
+    /// how many were left out. This overrides
+    /// [`PrettyOptions::max_items`](crate::PrettyOptions::max_items).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use debug2::{pprint, Debug, Formatter};
+    /// use std::fmt;
+    ///
+    /// struct Foo(Vec<i32>);
+    ///
+    /// impl Debug for Foo {
+    ///     fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+    ///         fmt.debug_list().limit(3).entries(self.0.iter()).finish()
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(pprint(Foo((0..10_000).collect())), "[0, 1, 2, ... 9_997 more]");
+    /// ```
+    pub fn limit(&mut self, max: usize) -> &mut Self {
+        self.inner.limit.set_max(max);
+        self
+    }
+
+    /// Adds `count` entries that were left out, like [`limit`](Self::limit)
+    /// does, with `at_least` if there may be more.
+    pub(crate) fn hidden(&mut self, count: usize, at_least: bool) -> &mut Self {
+        self.inner.limit.hide(count, at_least);
+        self
+    }
+
+    /// Shows `..` in place of the entries, as when it's too deep to show them.
+    pub(crate) fn elided(&mut self) -> &mut Self {
+        self.inner.limit.elide();
+        self
+    }
+
+    /// Lines up the fields of the entries, if they're all structs with the
+    /// same fields. This overrides
+    /// [`PrettyOptions::align`](crate::PrettyOptions::align), and must be
+    /// called before any entries are added.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use debug2::{pprint, Debug, Formatter};
+    /// use std::fmt;
+    ///
+    /// #[derive(Debug)]
+    /// struct Point {
+    ///     x: i32,
+    ///     y: i32,
+    /// }
+    ///
+    /// struct Path(Vec<Point>);
+    ///
+    /// impl Debug for Path {
+    ///     fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+    ///         fmt.debug_list().aligned().entries(&self.0).finish()
+    ///     }
+    /// }
+    ///
+    /// let path = Path((0..5).map(|i| Point { x: i * 7, y: 100 - i * i * 10 }).collect());
+    ///
+    /// assert_eq!(
+    ///     pprint(path),
+    ///     "\
+    /// [
+    ///     Point { x: 0,  y: 100 },
+    ///     Point { x: 7,  y: 90 },
+    ///     Point { x: 14, y: 60 },
+    ///     Point { x: 21, y: 10 },
+    ///     Point { x: 28, y: -60 },
+    /// ]"
+    /// );
+    /// ```
+    pub fn aligned(&mut self) -> &mut Self {
+        self.inner.set_align(Align::Fields);
+        self
+    }
+
+    /// Writes the entries as a table, if they're all structs with the same
+    /// fields. This overrides
+    /// [`PrettyOptions::align`](crate::PrettyOptions::align), and must be
+    /// called before any entries are added.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use debug2::{pprint, Debug, Formatter};
+    /// use std::fmt;
+    ///
+    /// #[derive(Debug)]
+    /// struct User {
+    ///     name: &'static str,
+    ///     age: u32,
+    /// }
+    ///
+    /// struct Users(Vec<User>);
+    ///
+    /// impl Debug for Users {
+    ///     fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+    ///         fmt.debug_list().table().entries(&self.0).finish()
+    ///     }
+    /// }
+    ///
+    /// let users = Users(vec![
+    ///     User { name: "Alice", age: 31 },
+    ///     User { name: "Bob", age: 4 },
+    ///     User { name: "Christopher", age: 102 },
+    /// ]);
+    ///
+    /// assert_eq!(
+    ///     pprint(users),
+    ///     "\
+    /// [
+    ///     name          | age
+    ///     --------------+----
+    ///     \"Alice\"       | 31
+    ///     \"Bob\"         | 4
+    ///     \"Christopher\" | 102
+    /// ]"
+    /// );
+    /// ```
+    pub fn table(&mut self) -> &mut Self {
+        self.inner.set_align(Align::Table);
+        self
+    }
+
     /// Adds a new entry to the list output.
     ///
     /// # Examples
@@ -663,7 +1645,6 @@ impl<'a, 'b: 'a> DebugList<'a, 'b> {
     ///     "[[10, 11], [12, 13]]",
     /// );
     /// ```
-
     pub fn entry(&mut self, entry: &dyn Debug) -> &mut Self {
         self.inner.entry(entry);
         self
@@ -690,15 +1671,12 @@ impl<'a, 'b: 'a> DebugList<'a, 'b> {
     ///
     /// assert_eq!(pprint(Foo(vec![10, 11], vec![12, 13])), "[10, 11, 12, 13]",);
     /// ```
-
     pub fn entries<D, I>(&mut self, entries: I) -> &mut Self
     where
         D: Debug,
         I: IntoIterator<Item = D>,
     {
-        for entry in entries {
-            self.entry(&entry);
-        }
+        self.inner.entries(entries);
         self
     }
 
@@ -720,11 +1698,8 @@ impl<'a, 'b: 'a> DebugList<'a, 'b> {
     ///
     /// assert_eq!(pprint(Foo(vec![10, 11])), "[10, 11]",);
     /// ```
-
     pub fn finish(&mut self) -> fmt::Result {
-        self.inner
-            .result
-            .and_then(|_| self.inner.fmt.write_str("]"))
+        self.inner.finish("]")
     }
 }
 
@@ -758,7 +1733,6 @@ impl<'a, 'b: 'a> DebugList<'a, 'b> {
 /// ```
 #[must_use = "must eventually call `finish()` on Debug builders"]
 #[allow(missing_debug_implementations)]
-
 pub struct DebugMap<'a, 'b: 'a> {
     fmt: &'a mut Formatter<'b>,
     result: fmt::Result,
@@ -766,20 +1740,124 @@ pub struct DebugMap<'a, 'b: 'a> {
     has_key: bool,
     // The state of newlines is tracked between keys and values
     state: PadAdapterState,
+    limit: Limit,
+    // Whether the current entry is past the limit
+    skip_value: bool,
+    // The width to pad keys to, see `PrettyOptions::align_keys`.
+    key_width: Option<usize>,
+    // Whether to sort entries given to `entries`.
+    sorted: bool,
+    // Whether `fmt.depth` has been put back, see `leave_depth`.
+    depth_left: bool,
 }
 
 pub(super) fn debug_map_new<'a, 'b>(fmt: &'a mut Formatter<'b>) -> DebugMap<'a, 'b> {
-    let result = fmt.write_str("{");
+    let result = match fmt.sink() {
+        Some(sink) => sink.begin_map(),
+        None => fmt.write_punct("{"),
+    };
+    let limit = Limit::new(fmt);
+    record_builder(fmt, None);
+    let key_width = fmt.key_width.take();
+    fmt.depth += 1;
+    fmt.nested = true;
     DebugMap {
         fmt,
         result,
         has_fields: false,
         has_key: false,
         state: Default::default(),
+        limit,
+        skip_value: false,
+        key_width,
+        sorted: false,
+        depth_left: false,
     }
 }
 
 impl<'a, 'b: 'a> DebugMap<'a, 'b> {
+    /// Limits the number of entries shown.
+    ///
+    /// Entries past the limit aren't formatted, and are replaced by a count of
+    /// how many were left out. This overrides
+    /// [`PrettyOptions::max_items`](crate::PrettyOptions::max_items).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use debug2::{pprint, Debug, Formatter};
+    /// use std::fmt;
+    ///
+    /// struct Foo(Vec<(String, i32)>);
+    ///
+    /// impl Debug for Foo {
+    ///     fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+    ///         fmt.debug_map()
+    ///             .limit(1)
+    ///             .entries(self.0.iter().map(|&(ref k, ref v)| (k, v)))
+    ///             .finish()
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(
+    ///     pprint(Foo(vec![("A".to_string(), 10), ("B".to_string(), 11)])),
+    ///     "{\"A\": 10, ... 1 more}",
+    /// );
+    /// ```
+    pub fn limit(&mut self, max: usize) -> &mut Self {
+        self.limit.set_max(max);
+        self
+    }
+
+    /// Adds `count` entries that were left out, like [`limit`](Self::limit)
+    /// does, with `at_least` if there may be more.
+    pub(crate) fn hidden(&mut self, count: usize, at_least: bool) -> &mut Self {
+        self.limit.hide(count, at_least);
+        self
+    }
+
+    /// Shows `..` in place of the entries, as when it's too deep to show them.
+    pub(crate) fn elided(&mut self) -> &mut Self {
+        self.limit.elide();
+        self
+    }
+
+    /// Sorts the entries given to [`entries`](Self::entries) by their keys,
+    /// so the output doesn't depend on the order they're stored in.
+    ///
+    /// Keys are ordered by how they're written on one line, with numbers
+    /// compared by value. Entries added one at a time with
+    /// [`entry`](Self::entry) or [`key`](Self::key) are shown in the order
+    /// they're added. If there are more than the [`limit`](Self::limit), only
+    /// the first 10,000 are sorted, so an endless iterator still finishes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use debug2::{pprint, Debug, Formatter};
+    /// use std::fmt;
+    ///
+    /// struct Foo(Vec<(u32, &'static str)>);
+    ///
+    /// impl Debug for Foo {
+    ///     fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+    ///         fmt.debug_map()
+    ///             .sorted()
+    ///             .entries(self.0.iter().map(|(k, v)| (k, v)))
+    ///             .finish()
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(
+    ///     pprint(Foo(vec![(10, "ten"), (9, "nine"), (100, "hundred")])),
+    ///     "{9: \"nine\", 10: \"ten\", 100: \"hundred\"}",
+    /// );
+    /// ```
+    pub fn sorted(&mut self) -> &mut Self {
+        self.sorted = true;
+        self
+    }
+
     /// Adds a new entry to the map output.
     ///
     /// # Examples
@@ -803,7 +1881,6 @@ impl<'a, 'b: 'a> DebugMap<'a, 'b> {
     ///     "{\"whole\": [(\"A\", 10), (\"B\", 11)]}",
     /// );
     /// ```
-
     pub fn entry(&mut self, key: &dyn Debug, value: &dyn Debug) -> &mut Self {
         self.key(key).value(value)
     }
@@ -841,7 +1918,6 @@ impl<'a, 'b: 'a> DebugMap<'a, 'b> {
     ///     "{\"whole\": [(\"A\", 10), (\"B\", 11)]}",
     /// );
     /// ```
-
     pub fn key(&mut self, key: &dyn Debug) -> &mut Self {
         self.result = self.result.and_then(|_| {
             assert!(
@@ -850,21 +1926,36 @@ impl<'a, 'b: 'a> DebugMap<'a, 'b> {
                                     without completing the previous one"
             );
 
-            if self.is_pretty() {
-                if !self.has_fields {
-                    self.fmt.write_str("\n")?;
-                }
+            self.skip_value = self.limit.skip();
+            if self.skip_value {
+                self.has_key = true;
+                return Ok(());
+            }
+
+            if self.fmt.sink.is_some() {
+                key.fmt(self.fmt)?;
+                self.fmt.end_scalar()?;
+            } else if self.is_pretty() {
+                let prefix = if self.has_fields { ",\n" } else { "\n" };
+                self.fmt.write_punct(prefix)?;
                 let mut slot = None;
                 self.state = Default::default();
-                let mut writer = PadAdapter::wrap(&mut self.fmt, &mut slot, &mut self.state);
+                let mut writer = PadAdapter::wrap(self.fmt, &mut slot, &mut self.state);
                 write_maybe_short(&key, &mut writer)?;
-                writer.write_str(": ")?;
             } else {
                 if self.has_fields {
-                    self.fmt.write_str(", ")?
+                    self.fmt.write_punct(", ")?
                 }
+                let start = self.fmt.column.get();
                 key.fmt(self.fmt)?;
-                self.fmt.write_str(": ")?;
+                if let Some(row) = self.fmt.row {
+                    let mut row = row.borrow_mut();
+                    if row.is_recording(self.fmt) {
+                        row.record_key(self.fmt.column.get() - start);
+                    }
+                }
+                let opts = self.fmt.opts;
+                self.fmt.write_punct(&opts.field_separator)?;
             }
 
             self.has_key = true;
@@ -907,7 +1998,6 @@ impl<'a, 'b: 'a> DebugMap<'a, 'b> {
     ///     "{\"whole\": [(\"A\", 10), (\"B\", 11)]}",
     /// );
     /// ```
-
     pub fn value(&mut self, value: &dyn Debug) -> &mut Self {
         self.result = self.result.and_then(|_| {
             assert!(
@@ -915,20 +2005,25 @@ impl<'a, 'b: 'a> DebugMap<'a, 'b> {
                 "attempted to format a map value before its key"
             );
 
+            if self.skip_value {
+                self.has_key = false;
+                return Ok(());
+            }
+
             if self.is_pretty() {
                 let mut slot = None;
-                let mut writer = PadAdapter::wrap(&mut self.fmt, &mut slot, &mut self.state);
-                write_maybe_short(value, &mut writer)?;
-                writer.write_str(",\n")?;
+                let mut writer = PadAdapter::wrap(self.fmt, &mut slot, &mut self.state);
+                write_key_value(&mut writer, self.key_width, value)?;
             } else {
                 value.fmt(self.fmt)?;
+                self.fmt.end_scalar()?;
             }
 
             self.has_key = false;
             Ok(())
         });
 
-        self.has_fields = true;
+        self.has_fields |= !self.skip_value;
         self
     }
 
@@ -957,19 +2052,44 @@ impl<'a, 'b: 'a> DebugMap<'a, 'b> {
     ///     "{\"A\": 10, \"B\": 11}",
     /// );
     /// ```
-
     pub fn entries<K, V, I>(&mut self, entries: I) -> &mut Self
     where
         K: Debug,
         V: Debug,
         I: IntoIterator<Item = (K, V)>,
     {
-        for (k, v) in entries {
-            self.entry(&k, &v);
+        let mut entries = entries.into_iter();
+        if self.sorted && !self.limit.elided {
+            let most = self.limit.most_sorted();
+            match sort_by_text(self.fmt, entries.by_ref().take(most), |(key, _)| key) {
+                Ok(sorted) => self.push_entries(sorted),
+                Err(e) => self.result = Err(e),
+            }
+            self.limit.skip_rest(entries);
+        } else {
+            self.push_entries(entries);
         }
         self
     }
 
+    fn push_entries<K, V, I>(&mut self, entries: I)
+    where
+        K: Debug,
+        V: Debug,
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut entries = entries.into_iter();
+        while !self.limit.is_full() {
+            match entries.next() {
+                Some((k, v)) => {
+                    self.entry(&k, &v);
+                }
+                None => return,
+            }
+        }
+        self.limit.skip_rest(entries);
+    }
+
     /// Finishes output and returns any error encountered.
     ///
     /// # Panics
@@ -998,16 +2118,21 @@ impl<'a, 'b: 'a> DebugMap<'a, 'b> {
     ///     "{\"A\": 10, \"B\": 11}",
     /// );
     /// ```
-
     pub fn finish(&mut self) -> fmt::Result {
-        self.result.and_then(|_| {
+        let result = self.result.and_then(|_| {
             assert!(
                 !self.has_key,
                 "attempted to finish a map with a partial entry"
             );
 
-            self.fmt.write_str("}")
-        })
+            if let Some(sink) = self.fmt.sink() {
+                return self.limit.sink_end(sink);
+            }
+            self.limit.write_end(self.fmt, self.has_fields, true)?;
+            self.fmt.write_punct("}")
+        });
+        leave_depth(self.fmt, &mut self.depth_left);
+        result
     }
 
     fn is_pretty(&self) -> bool {
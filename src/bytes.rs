@@ -0,0 +1,200 @@
+use std::fmt::Write;
+
+use crate::strings::write_wrapped;
+use crate::{Debug, Formatter, Result, TokenKind};
+
+/// How byte slices are shown.
+///
+/// This is set with [`PrettyOptions::bytes`](crate::PrettyOptions::bytes), and
+/// applies to `[u8]`, `Vec<u8>` and [`Bytes`].
+///
+/// # Examples
+///
+/// ```rust
+/// use debug2::{pprint_with, BytesStyle, PrettyOptions};
+///
+/// let data = b"hi!\0";
+///
+/// let style = |style| pprint_with(data, &PrettyOptions::new().bytes(style));
+///
+/// assert_eq!(style(BytesStyle::List), "[104, 105, 33, 0]");
+/// assert_eq!(style(BytesStyle::Escaped), r#"b"hi!\x00""#);
+/// assert_eq!(style(BytesStyle::Hex), "[68 69 21 00]");
+/// assert_eq!(style(BytesStyle::HexDump), "[00000000: 6869 2100  hi!.]");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, std::fmt::Debug)]
+pub enum BytesStyle {
+    /// A list of numbers, like any other slice: `[104, 105, 0]`.
+    List,
+    /// A byte string literal: `b"hi\x00"`.
+    ///
+    /// Long strings are split over multiple lines, with `\` at the end of each line.
+    Escaped,
+    /// Space separated hex bytes: `[68 69 00]`.
+    Hex,
+    /// Lines of offsets, hex and ASCII, like `xxd`.
+    ///
+    /// ```text
+    /// [
+    ///     00000000: 4865 6c6c 6f2c 2077 6f72 6c64 2120 4865  Hello, world! He
+    ///     00000010: 6c6c 6f21                                llo!
+    /// ]
+    /// ```
+    HexDump,
+}
+
+/// Formats a byte slice as bytes, instead of as a list of numbers.
+///
+/// This uses the style set by [`PrettyOptions::bytes`](crate::PrettyOptions::bytes),
+/// or [`BytesStyle::Escaped`] if that is [`BytesStyle::List`].
+///
+/// # Examples
+///
+/// ```rust
+/// use debug2::{pprint, Bytes, Debug};
+///
+/// #[derive(Debug)]
+/// struct Packet<'a> {
+///     id: u32,
+///     body: Bytes<'a>,
+/// }
+///
+/// let packet = Packet {
+///     id: 7,
+///     body: Bytes(b"GET / HTTP/1.1\r\n"),
+/// };
+///
+/// assert_eq!(
+///     pprint(packet),
+///     r#"Packet { id: 7, body: b"GET / HTTP/1.1\r\n" }"#
+/// );
+/// ```
+#[derive(Clone, Copy)]
+pub struct Bytes<'a>(pub &'a [u8]);
+
+impl Debug for Bytes<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match f.opts.bytes {
+            BytesStyle::List => fmt_bytes(self.0, BytesStyle::Escaped, f),
+            style => fmt_bytes(self.0, style, f),
+        }
+    }
+}
+
+pub(crate) fn fmt_bytes(bytes: &[u8], style: BytesStyle, f: &mut Formatter<'_>) -> Result {
+    match style {
+        BytesStyle::List => f.debug_list().entries(bytes).finish(),
+        BytesStyle::Escaped => fmt_escaped(bytes, f),
+        BytesStyle::Hex => fmt_hex(bytes, f),
+        BytesStyle::HexDump => fmt_hexdump(bytes, f),
+    }
+}
+
+fn fmt_escaped(bytes: &[u8], f: &mut Formatter<'_>) -> Result {
+    let escaped = bytes.iter().map(|&b| match b {
+        // Only needs escaping in char literals
+        b'\'' => "'".to_owned(),
+        _ => std::ascii::escape_default(b).to_string(),
+    });
+
+    if f.is_pretty() {
+        write_wrapped(f, "b\"", escaped, "  ", "\"")
+    } else {
+        f.write_token(TokenKind::String, "b\"")?;
+        for esc in escaped {
+            f.write_token(TokenKind::String, &esc)?;
+        }
+        f.write_token(TokenKind::String, "\"")
+    }
+}
+
+fn fmt_hex(bytes: &[u8], f: &mut Formatter<'_>) -> Result {
+    f.write_punct("[")?;
+
+    let per_line = if f.is_pretty() {
+        // Each byte takes 3 columns, and we're indented inside the brackets.
+        let width = f.remaining_width().saturating_sub(f.opts.indent_width());
+        (width + 1) / 3 / 4 * 4
+    } else {
+        bytes.len()
+    };
+
+    for (n, chunk) in bytes.chunks(per_line.max(4)).enumerate() {
+        if f.is_pretty() {
+            f.write_str("\n")?;
+            f.write_str(&f.opts.indent)?;
+        } else if n != 0 {
+            f.write_str(" ")?;
+        }
+
+        let mut line = String::new();
+        for (i, b) in chunk.iter().enumerate() {
+            if i != 0 {
+                line.push(' ');
+            }
+            write!(line, "{:02x}", b)?;
+        }
+        f.write_token(TokenKind::Number, &line)?;
+    }
+
+    if f.is_pretty() && !bytes.is_empty() {
+        f.write_str("\n")?;
+    }
+    f.write_punct("]")
+}
+
+fn fmt_hexdump(bytes: &[u8], f: &mut Formatter<'_>) -> Result {
+    // Inside the brackets, lines are indented one more level.
+    let width = f.remaining_width().saturating_sub(f.opts.indent_width());
+
+    // Each line is 10 columns of offset, 5 columns for every 2 bytes of hex
+    // (less one trailing space), 2 columns of gap, and 1 column per byte of
+    // ASCII. Use the largest multiple of 4, up to 16, that fits.
+    let per_line = [16, 12, 8, 4]
+        .iter()
+        .copied()
+        .find(|n| 10 + (n * 5 / 2 - 1) + 2 + n <= width)
+        .unwrap_or(4);
+    let hex_width = per_line * 5 / 2 - 1;
+
+    f.write_punct("[")?;
+
+    for (n, chunk) in bytes.chunks(per_line).enumerate() {
+        if f.is_pretty() {
+            f.write_str("\n")?;
+            f.write_str(&f.opts.indent)?;
+        } else if n != 0 {
+            f.write_str(" ")?;
+        }
+
+        let mut hex = String::new();
+        for (i, b) in chunk.iter().enumerate() {
+            if i != 0 && i % 2 == 0 {
+                hex.push(' ');
+            }
+            write!(hex, "{:02x}", b)?;
+        }
+
+        let ascii: String = chunk
+            .iter()
+            .map(|&b| match b {
+                b' '..=b'~' => b as char,
+                _ => '.',
+            })
+            .collect();
+
+        let line = format!(
+            "{:08x}: {:hex_width$}  {}",
+            n * per_line,
+            hex,
+            ascii,
+            hex_width = if f.is_pretty() { hex_width } else { 0 }
+        );
+        f.write_str(&line)?;
+    }
+
+    if f.is_pretty() && !bytes.is_empty() {
+        f.write_str("\n")?;
+    }
+    f.write_punct("]")
+}
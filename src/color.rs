@@ -0,0 +1,133 @@
+use std::borrow::Cow;
+
+/// The kinds of text that can be shown in different colors.
+///
+/// See [`Theme`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, std::fmt::Debug)]
+pub enum TokenKind {
+    /// The name of a struct, tuple struct or enum variant: `Some`, `Point`.
+    Name,
+    /// The name of a struct field.
+    Field,
+    /// A string or byte string literal.
+    String,
+    /// An integer or float.
+    Number,
+    /// `true`, `false` and `None`.
+    Keyword,
+    /// Brackets, commas, separators and `..`.
+    Punct,
+    /// Anything else, like an IP address or a time.
+    Other,
+}
+
+const KINDS: usize = 7;
+
+/// The colors used for each [`TokenKind`], as used by
+/// [`PrettyOptions::theme`](crate::PrettyOptions::theme).
+///
+/// Colors are given as the parameters of an [ANSI SGR escape
+/// sequence](https://en.wikipedia.org/wiki/ANSI_escape_code#SGR), so `"1;34"`
+/// is bold blue.
+///
+/// # Examples
+///
+/// ```rust
+/// use debug2::{pprint_with, PrettyOptions, Theme, TokenKind};
+///
+/// let theme = Theme::empty()
+///     .style(TokenKind::Number, "33")
+///     .style(TokenKind::Keyword, "1;35");
+///
+/// assert_eq!(
+///     pprint_with((1, true), &PrettyOptions::new().theme(theme)),
+///     "(\x1b[33m1\x1b[0m, \x1b[1;35mtrue\x1b[0m)"
+/// );
+/// ```
+#[derive(Clone, PartialEq, Eq, std::fmt::Debug)]
+pub struct Theme {
+    styles: [Option<String>; KINDS],
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::empty()
+            .style(TokenKind::Name, "1")
+            .style(TokenKind::Field, "36")
+            .style(TokenKind::String, "32")
+            .style(TokenKind::Number, "33")
+            .style(TokenKind::Keyword, "35")
+    }
+}
+
+impl Theme {
+    /// Creates the default theme, which leaves punctuation and
+    /// [`TokenKind::Other`] uncolored.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a theme without any colors.
+    pub fn empty() -> Self {
+        Theme {
+            styles: Default::default(),
+        }
+    }
+
+    /// Sets the color of a kind of text, as the parameters of an SGR escape
+    /// sequence, like `"1;34"`.
+    ///
+    /// An empty string leaves it uncolored.
+    pub fn style(mut self, kind: TokenKind, sgr: impl Into<String>) -> Self {
+        let sgr = sgr.into();
+        self.styles[kind as usize] = if sgr.is_empty() { None } else { Some(sgr) };
+        self
+    }
+
+    /// Wraps `text` in the escape sequences for `kind`, leaving whitespace at
+    /// either end uncolored.
+    pub(crate) fn paint<'t>(&self, kind: TokenKind, text: &'t str) -> Cow<'t, str> {
+        let sgr = match &self.styles[kind as usize] {
+            Some(sgr) if !text.trim().is_empty() => sgr,
+            _ => return Cow::Borrowed(text),
+        };
+        let start = text.len() - text.trim_start().len();
+        let end = text.trim_end().len();
+        Cow::Owned(format!(
+            "{}\x1b[{}m{}\x1b[0m{}",
+            &text[..start],
+            sgr,
+            &text[start..end],
+            &text[end..]
+        ))
+    }
+}
+
+/// Removes any escape sequences from `text`.
+pub(crate) fn strip_escapes(text: &str) -> Cow<'_, str> {
+    if !text.contains('\x1b') {
+        return Cow::Borrowed(text);
+    }
+    let mut stripped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            skip_escape(&mut chars);
+        } else {
+            stripped.push(c);
+        }
+    }
+    Cow::Owned(stripped)
+}
+
+/// Skips the rest of an escape sequence, after the `\x1b`.
+pub(crate) fn skip_escape(chars: &mut std::str::Chars<'_>) {
+    if chars.next() != Some('[') {
+        return;
+    }
+    for c in chars {
+        if ('@'..='~').contains(&c) {
+            break;
+        }
+    }
+}
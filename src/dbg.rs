@@ -0,0 +1,261 @@
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+use crate::{Debug, PrettyOptions};
+
+/// A function set with [`set_dbg_sink`].
+///
+/// It must be `Send`, as one sink is shared by every thread, and is called on
+/// whichever thread calls `dbg!`.
+pub type DbgSink = Box<dyn FnMut(&DbgRecord<'_>) + Send>;
+
+static SINK: Mutex<Option<DbgSink>> = Mutex::new(None);
+// Whether `SINK` is set, so `dbg!` doesn't have to lock it to find out.
+static HAS_SINK: AtomicBool = AtomicBool::new(false);
+
+/// What a call to [`dbg!`](crate::dbg) printed, given to the function set with
+/// [`set_dbg_sink`].
+#[derive(Clone, Copy, std::fmt::Debug)]
+pub struct DbgRecord<'a> {
+    file: &'a str,
+    line: u32,
+    column: u32,
+    module_path: &'a str,
+    expr: &'a str,
+    value: &'a str,
+}
+
+impl<'a> DbgRecord<'a> {
+    /// The file `dbg!` was called in.
+    pub fn file(&self) -> &'a str {
+        self.file
+    }
+
+    /// The line `dbg!` was called on.
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+
+    /// The column `dbg!` was called at.
+    pub fn column(&self) -> u32 {
+        self.column
+    }
+
+    /// The path of the module `dbg!` was called in, like `my_crate::parser`.
+    pub fn module_path(&self) -> &'a str {
+        self.module_path
+    }
+
+    /// The text of the expression, or an empty string for `dbg!()`.
+    pub fn expr(&self) -> &'a str {
+        self.expr
+    }
+
+    /// The value of the expression, pretty printed as [`pprint`](crate::pprint)
+    /// would, or an empty string for `dbg!()`.
+    pub fn value(&self) -> &'a str {
+        self.value
+    }
+}
+
+/// Sends everything [`dbg!`](crate::dbg) prints to `sink`, instead of stderr.
+///
+/// The sink is used by every thread, and replaces any set before. Calling
+/// `dbg!` from inside the sink will deadlock.
+///
+/// # Examples
+///
+/// ```rust
+/// use debug2::{dbg, set_dbg_sink};
+/// use std::sync::{Arc, Mutex};
+///
+/// let seen = Arc::new(Mutex::new(Vec::new()));
+/// let sink_seen = Arc::clone(&seen);
+/// set_dbg_sink(Box::new(move |record| {
+///     let line = format!("{} = {}", record.expr(), record.value());
+///     sink_seen.lock().unwrap().push(line);
+/// }));
+///
+/// dbg!(1 + 2);
+/// debug2::take_dbg_sink();
+///
+/// assert_eq!(*seen.lock().unwrap(), ["1 + 2 = 3"]);
+/// ```
+pub fn set_dbg_sink(sink: DbgSink) {
+    let mut slot = SINK.lock().unwrap_or_else(PoisonError::into_inner);
+    *slot = Some(sink);
+    HAS_SINK.store(true, Ordering::Release);
+}
+
+/// Removes the sink set with [`set_dbg_sink`], so [`dbg!`](crate::dbg) prints
+/// to stderr again, and returns it.
+pub fn take_dbg_sink() -> Option<DbgSink> {
+    let mut slot = SINK.lock().unwrap_or_else(PoisonError::into_inner);
+    HAS_SINK.store(false, Ordering::Release);
+    slot.take()
+}
+
+/// Gives `record` to the sink, returning `false` if there isn't one.
+fn send(record: &DbgRecord<'_>) -> bool {
+    if !HAS_SINK.load(Ordering::Acquire) {
+        return false;
+    }
+    // Only the call to the sink is made holding the lock, so other threads
+    // can format their values while it runs.
+    match &mut *SINK.lock().unwrap_or_else(PoisonError::into_inner) {
+        Some(sink) => {
+            sink(record);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Prints the value of an expression given to [`dbg!`](crate::dbg).
+#[doc(hidden)]
+pub fn print<T: Debug + ?Sized>(
+    file: &str,
+    line: u32,
+    column: u32,
+    module_path: &str,
+    expr: &str,
+    value: &T,
+) {
+    if !enabled(module_path) {
+        return;
+    }
+    if HAS_SINK.load(Ordering::Acquire) {
+        let value = crate::pprint(value);
+        let record = DbgRecord {
+            file,
+            line,
+            column,
+            module_path,
+            expr,
+            value: &value,
+        };
+        if send(&record) {
+            return;
+        }
+    }
+    eprintln!("{}", message(file, line, expr, value));
+}
+
+/// Prints where [`dbg!`](crate::dbg) was called with no arguments.
+#[doc(hidden)]
+pub fn print_here(file: &str, line: u32, column: u32, module_path: &str) {
+    if !enabled(module_path) {
+        return;
+    }
+    let record = DbgRecord {
+        file,
+        line,
+        column,
+        module_path,
+        expr: "",
+        value: "",
+    };
+    if !send(&record) {
+        eprintln!("[{}:{}]", file, line);
+    }
+}
+
+/// Writes `[file:line] expr = value`, with the lines of the value after the
+/// first lined up with the first.
+fn message<T: Debug + ?Sized>(file: &str, line: u32, expr: &str, value: &T) -> String {
+    let prefix = format!("[{}:{}] {} = ", file, line, expr);
+    let defaults = PrettyOptions::current();
+    let indent_by = crate::text_width(&prefix);
+    let opts = PrettyOptions {
+        width: defaults.width.saturating_sub(indent_by),
+        ..defaults.clone()
+    };
+    let text = crate::pprint_with(value, &opts);
+
+    // Lining up under a long prefix would leave too little room, so start on
+    // the next line instead.
+    if text.contains('\n') && indent_by > defaults.width / 2 {
+        let opts = PrettyOptions {
+            width: defaults.width.saturating_sub(defaults.indent_width()),
+            ..defaults.clone()
+        };
+        let text = crate::pprint_with(value, &opts);
+        return format!(
+            "{}\n{}",
+            prefix.trim_end(),
+            indent(&text, &defaults.indent, true)
+        );
+    }
+
+    format!("{}{}", prefix, indent(&text, &" ".repeat(indent_by), false))
+}
+
+/// Indents every line of `text` after the first, and the first too if `first`.
+fn indent(text: &str, indent: &str, first: bool) -> String {
+    let mut out = String::with_capacity(text.len());
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        if (i > 0 || first) && !line.is_empty() {
+            out.push_str(indent);
+        }
+        out.push_str(line);
+    }
+    out
+}
+
+/// Which modules `dbg!` prints in, from `DEBUG2_DBG`.
+///
+/// This is a comma separated list of module paths, each covering the module
+/// and the modules inside it. Paths starting with `-` are silenced. If any
+/// paths don't start with `-`, only the modules they cover are printed. When
+/// several paths cover a module, the longest wins.
+struct Filter {
+    // The module paths, and whether each is printed.
+    paths: Vec<(String, bool)>,
+    // Whether modules not covered by any path are printed.
+    rest: bool,
+}
+
+impl Filter {
+    fn parse(spec: &str) -> Filter {
+        let paths: Vec<_> = spec
+            .split(',')
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .map(|path| match path.strip_prefix('-') {
+                Some(path) => (path.trim().to_owned(), false),
+                None => (path.to_owned(), true),
+            })
+            .collect();
+        let rest = !paths.iter().any(|&(_, shown)| shown);
+        Filter { paths, rest }
+    }
+
+    fn enabled(&self, module_path: &str) -> bool {
+        self.paths
+            .iter()
+            .filter(|(path, _)| covers(path, module_path))
+            .max_by_key(|(path, _)| path.len())
+            .map_or(self.rest, |&(_, shown)| shown)
+    }
+}
+
+/// Whether `module_path` is `path`, or a module inside it.
+fn covers(path: &str, module_path: &str) -> bool {
+    match module_path.strip_prefix(path) {
+        Some(rest) => rest.is_empty() || rest.starts_with("::"),
+        None => false,
+    }
+}
+
+fn enabled(module_path: &str) -> bool {
+    static FILTER: OnceLock<Option<Filter>> = OnceLock::new();
+    let filter =
+        FILTER.get_or_init(|| env::var("DEBUG2_DBG").ok().map(|spec| Filter::parse(&spec)));
+    filter
+        .as_ref()
+        .map_or(true, |filter| filter.enabled(module_path))
+}
@@ -0,0 +1,325 @@
+use crate::value::to_value_with;
+use crate::{Debug, PrettyOptions, Value};
+
+/// Shows how two items differ, as only the parts that changed.
+///
+/// Both items are broken down into their struct fields, tuple fields, list
+/// entries and map entries, and only the paths to parts that differ are
+/// shown. Lines starting with `-` are only in `left`, and lines starting with
+/// `+` are only in `right`. Anything the same on both sides is left out, with
+/// `..` showing where. If the items are the same, this is empty.
+///
+/// ```rust
+/// use debug2::{diff, Debug};
+///
+/// #[derive(Debug)]
+/// struct Config {
+///     name: &'static str,
+///     retries: u32,
+///     hosts: Vec<&'static str>,
+/// }
+///
+/// let left = Config {
+///     name: "prod",
+///     retries: 3,
+///     hosts: vec!["a", "b", "c"],
+/// };
+/// let right = Config {
+///     name: "prod",
+///     retries: 5,
+///     hosts: vec!["a", "c", "d"],
+/// };
+///
+/// assert_eq!(
+///     diff(&left, &right),
+///     " Config {
+///      ..
+/// -    retries: 3,
+/// +    retries: 5,
+///      hosts: [
+///          ..
+/// -        \"b\",
+///          ..
+/// +        \"d\",
+///      ],
+///  }"
+/// );
+///
+/// assert_eq!(diff(&left, &left), "");
+/// ```
+pub fn diff<L: Debug, R: Debug>(left: L, right: R) -> String {
+    diff_with(left, right, &PrettyOptions::default())
+}
+
+/// Shows how two items differ using the given options.
+///
+/// See [`diff`] for an example.
+pub fn diff_with<L: Debug, R: Debug>(left: L, right: R, opts: &PrettyOptions) -> String {
+    let left = to_value_with(left, opts);
+    let right = to_value_with(right, opts);
+    let mut diff = Diff {
+        opts,
+        lines: Vec::new(),
+    };
+    if left != right {
+        diff.value(0, "", &left, &right, "");
+    }
+    diff.lines.join("\n")
+}
+
+struct Diff<'a> {
+    opts: &'a PrettyOptions,
+    lines: Vec<String>,
+}
+
+/// A field, or an entry of a list, set or map, with what to write before its
+/// value.
+struct Item<'v> {
+    prefix: String,
+    value: &'v Value,
+}
+
+/// How entries of two lists line up.
+#[derive(Clone, Copy)]
+enum Edit {
+    /// `left[i]` matches `right[j]`.
+    Both(usize, usize),
+    Left(usize),
+    Right(usize),
+}
+
+impl Diff<'_> {
+    /// Shows how two values that aren't equal differ.
+    fn value(&mut self, depth: usize, prefix: &str, left: &Value, right: &Value, comma: &str) {
+        let opts = self.opts;
+        let (left_value, right_value) = (left, right);
+        let (open, close, left, right, edits) = match (left, right) {
+            (
+                Value::Struct { name, fields },
+                Value::Struct {
+                    name: right_name,
+                    fields: right_fields,
+                },
+            ) if name == right_name => {
+                let (left, right) = (fields_items(fields, opts), fields_items(right_fields, opts));
+                let edits = align(&left, &right, |l, r| l.prefix == r.prefix);
+                (format!("{} {{", name), "}", left, right, edits)
+            }
+            (
+                Value::Tuple { name, fields },
+                Value::Tuple {
+                    name: right_name,
+                    fields: right_fields,
+                },
+            ) if name == right_name && fields.len() == right_fields.len() => {
+                let edits = (0..fields.len()).map(|i| Edit::Both(i, i)).collect();
+                let (left, right) = (unkeyed(fields), unkeyed(right_fields));
+                (format!("{}(", name), ")", left, right, edits)
+            }
+            (Value::List(left_entries), Value::List(right_entries))
+            | (Value::Set(left_entries), Value::Set(right_entries)) => {
+                let (left, right) = (unkeyed(left_entries), unkeyed(right_entries));
+                let edits = align(&left, &right, |l, r| l.value == r.value);
+                if mostly_changed_atoms(&left, &right, &edits) {
+                    self.whole('-', depth, prefix, left_value, comma);
+                    self.whole('+', depth, prefix, right_value, comma);
+                    return;
+                }
+                let (open, close) = match left_value {
+                    Value::List(_) => ("[", "]"),
+                    _ => ("{", "}"),
+                };
+                (open.to_owned(), close, left, right, edits)
+            }
+            (Value::Map(left), Value::Map(right)) => {
+                let (left, right) = (map_items(left, opts), map_items(right, opts));
+                let edits = align(&left, &right, |l, r| l.prefix == r.prefix);
+                ("{".to_owned(), "}", left, right, edits)
+            }
+            _ => {
+                self.whole('-', depth, prefix, left, comma);
+                self.whole('+', depth, prefix, right, comma);
+                return;
+            }
+        };
+
+        self.line(' ', depth, &format!("{}{}", prefix, open));
+        self.entries(depth + 1, &left, &right, &edits);
+        self.line(' ', depth, &format!("{}{}", close, comma));
+    }
+
+    /// Writes the entries that differ, given how they line up.
+    fn entries(&mut self, depth: usize, left: &[Item<'_>], right: &[Item<'_>], edits: &[Edit]) {
+        let mut skipped = false;
+        let mut i = 0;
+        while i < edits.len() {
+            if let Edit::Both(l, r) = edits[i] {
+                i += 1;
+                if left[l].value == right[r].value {
+                    skipped = true;
+                    continue;
+                }
+                self.skipped(depth, &mut skipped);
+                self.value(depth, &left[l].prefix, left[l].value, right[r].value, ",");
+                continue;
+            }
+
+            // Entries removed and added in the same place are most likely
+            // changed, so show how they changed.
+            let (mut removed, mut added) = (Vec::new(), Vec::new());
+            while let Some(&edit) = edits.get(i) {
+                match edit {
+                    Edit::Left(l) => removed.push(&left[l]),
+                    Edit::Right(r) => added.push(&right[r]),
+                    Edit::Both(..) => break,
+                }
+                i += 1;
+            }
+            self.skipped(depth, &mut skipped);
+            let paired = removed.len().min(added.len());
+            for (l, r) in removed.iter().zip(&added) {
+                self.value(depth, &l.prefix, l.value, r.value, ",");
+            }
+            for l in &removed[paired..] {
+                self.whole('-', depth, &l.prefix, l.value, ",");
+            }
+            for r in &added[paired..] {
+                self.whole('+', depth, &r.prefix, r.value, ",");
+            }
+        }
+        self.skipped(depth, &mut skipped);
+    }
+
+    /// Writes `..` in place of entries that are the same on both sides.
+    fn skipped(&mut self, depth: usize, skipped: &mut bool) {
+        if std::mem::take(skipped) {
+            self.line(' ', depth, "..");
+        }
+    }
+
+    /// Writes a value as `pprint` would, with every line marked.
+    fn whole(&mut self, marker: char, depth: usize, prefix: &str, value: &Value, comma: &str) {
+        let used = 1
+            + depth * self.opts.indent_width()
+            + crate::text_width(prefix)
+            + crate::text_width(comma);
+        let opts = PrettyOptions {
+            width: self.opts.width.saturating_sub(used),
+            ..self.opts.clone()
+        };
+        let text = crate::pprint_with(value, &opts);
+        let mut lines = text.lines().peekable();
+        let mut first = true;
+        while let Some(line) = lines.next() {
+            let prefix = if first { prefix } else { "" };
+            let comma = if lines.peek().is_none() { comma } else { "" };
+            self.line(marker, depth, &format!("{}{}{}", prefix, line, comma));
+            first = false;
+        }
+    }
+
+    fn line(&mut self, marker: char, depth: usize, text: &str) {
+        self.lines.push(format!(
+            "{}{}{}",
+            marker,
+            self.opts.indent.repeat(depth),
+            text
+        ));
+    }
+}
+
+/// Whether two lists of atoms have so little in common that they're easier
+/// to compare whole, filled as `pprint` would, than entry by entry.
+fn mostly_changed_atoms(left: &[Item<'_>], right: &[Item<'_>], edits: &[Edit]) -> bool {
+    let atoms = |items: &[Item<'_>]| {
+        items
+            .iter()
+            .all(|item| matches!(item.value, Value::Atom(..)))
+    };
+    let common = edits
+        .iter()
+        .filter(|edit| matches!(edit, Edit::Both(..)))
+        .count();
+    let changed = edits.len() - common;
+    atoms(left) && atoms(right) && changed > common
+}
+
+fn fields_items<'v>(fields: &'v [(String, Value)], opts: &PrettyOptions) -> Vec<Item<'v>> {
+    fields
+        .iter()
+        .map(|(name, value)| Item {
+            prefix: format!("{}{}", name, opts.field_separator),
+            value,
+        })
+        .collect()
+}
+
+fn map_items<'v>(entries: &'v [(Value, Value)], opts: &PrettyOptions) -> Vec<Item<'v>> {
+    entries
+        .iter()
+        .map(|(key, value)| Item {
+            prefix: format!(
+                "{}{}",
+                crate::flatprint_checked(key, opts, 0, opts.int_style).unwrap(),
+                opts.field_separator
+            ),
+            value,
+        })
+        .collect()
+}
+
+fn unkeyed(values: &[Value]) -> Vec<Item<'_>> {
+    values
+        .iter()
+        .map(|value| Item {
+            prefix: String::new(),
+            value,
+        })
+        .collect()
+}
+
+/// Lines up two lists, keeping as many matching entries as possible.
+fn align<T>(left: &[T], right: &[T], matches: impl Fn(&T, &T) -> bool) -> Vec<Edit> {
+    // Finding the longest common subsequence takes `left.len() *
+    // right.len()` space, so for long lists just line them up by index.
+    if left.len().saturating_mul(right.len()) > 1 << 20 {
+        let both = left.len().min(right.len());
+        return (0..both)
+            .map(|i| Edit::Both(i, i))
+            .chain((both..left.len()).map(Edit::Left))
+            .chain((both..right.len()).map(Edit::Right))
+            .collect();
+    }
+
+    // `common[i][j]` is how many entries `left[i..]` and `right[j..]` have in
+    // common.
+    let mut common = vec![vec![0usize; right.len() + 1]; left.len() + 1];
+    for i in (0..left.len()).rev() {
+        for j in (0..right.len()).rev() {
+            common[i][j] = if matches(&left[i], &right[j]) {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut edits = Vec::with_capacity(left.len().max(right.len()));
+    while i < left.len() && j < right.len() {
+        if matches(&left[i], &right[j]) {
+            edits.push(Edit::Both(i, j));
+            i += 1;
+            j += 1;
+        } else if common[i + 1][j] >= common[i][j + 1] {
+            edits.push(Edit::Left(i));
+            i += 1;
+        } else {
+            edits.push(Edit::Right(j));
+            j += 1;
+        }
+    }
+    edits.extend((i..left.len()).map(Edit::Left));
+    edits.extend((j..right.len()).map(Edit::Right));
+    edits
+}
@@ -0,0 +1,51 @@
+use std::error::Error;
+
+use crate::{Debug, Formatter, Result};
+
+/// Formats an error along with its chain of [`source`](Error::source)s.
+///
+/// Each error in the chain is shown as its [`Display`](std::fmt::Display)
+/// output, with the error that caused it nested inside.
+///
+/// This is also how `dyn Error` trait objects are formatted, but `ErrorReport`
+/// can be used with any concrete error type, and with errors that aren't
+/// `'static`.
+///
+/// # Examples
+///
+/// ```rust
+/// use debug2::{pprint, ErrorReport};
+/// use std::io;
+///
+/// let err = io::Error::new(io::ErrorKind::Other, "disk on fire");
+///
+/// assert_eq!(
+///     pprint(ErrorReport(&err)),
+///     "Error { message: \"disk on fire\" }"
+/// );
+/// ```
+///
+/// Only the message and source chain are shown. Context added by wrapping an
+/// error in another shows up as the wrapping error's message, as long as the
+/// wrapper returns the error it wraps from `source`. Other context attached
+/// to an error isn't shown.
+///
+/// Backtraces can't be retrieved from a `dyn Error` on stable Rust, so they
+/// aren't included either. If you have captured a
+/// [`Backtrace`](std::backtrace::Backtrace), it implements [`Debug`] too, and
+/// can be printed alongside the report.
+#[derive(Clone, Copy)]
+pub struct ErrorReport<'a>(pub &'a dyn Error);
+
+impl Debug for ErrorReport<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let mut res = f.debug_struct("Error");
+        res.field("message", &self.0.to_string());
+
+        if let Some(source) = self.0.source() {
+            res.field("source", &ErrorReport(source));
+        }
+
+        res.finish()
+    }
+}
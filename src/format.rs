@@ -0,0 +1,72 @@
+use std::cell::Cell;
+use std::fmt::{self, Display, Write};
+
+use crate::{Debug, Output, PrettyOptions};
+
+/// Formats the arguments of [`pformat!`](crate::pformat), keeping track of
+/// which column each one starts at.
+#[doc(hidden)]
+#[derive(Default)]
+pub struct Placeholders {
+    column: Cell<usize>,
+}
+
+impl Placeholders {
+    pub fn format(&self, args: fmt::Arguments<'_>) -> String {
+        let mut out = Output {
+            buf: String::new(),
+            column: &self.column,
+        };
+        out.write_fmt(args)
+            .expect("a Display implementation returned an error unexpectedly");
+        out.buf
+    }
+}
+
+/// An argument of [`pformat!`](crate::pformat), which is pretty printed with
+/// any lines after the first lined up with the first.
+#[doc(hidden)]
+pub struct Pretty<'a, T: ?Sized> {
+    placeholders: &'a Placeholders,
+    value: &'a T,
+}
+
+impl<'a, T: ?Sized> Pretty<'a, T> {
+    pub fn new(placeholders: &'a Placeholders, value: &'a T) -> Self {
+        Pretty {
+            placeholders,
+            value,
+        }
+    }
+}
+
+impl<T: Debug + ?Sized> Display for Pretty<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let column = self.placeholders.column.get();
+        let defaults = PrettyOptions::current();
+        let opts = PrettyOptions {
+            width: defaults.width.saturating_sub(column),
+            ..defaults
+        };
+        let text = crate::pprint_with_checked(self.value, &opts)?;
+        if !text.contains('\n') {
+            return f.pad(&text);
+        }
+        for (i, line) in text.split('\n').enumerate() {
+            if i > 0 {
+                f.write_char('\n')?;
+                if !line.is_empty() {
+                    write!(f, "{:1$}", "", column)?;
+                }
+            }
+            f.write_str(line)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Debug + ?Sized> fmt::Debug for Pretty<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
@@ -107,17 +107,48 @@
 //! - Prevalence: Almost every type implements [`std::fmt::Debug`], but not this type
 //! - The derive isn't great: The derive macro for [`std::fmt::Debug`] works everywhere. This one
 //!   is kind of basic, and will probably not work everywhere it should.
+//! - Function pointers: [`Debug`] is implemented for safe and unsafe function pointers with up to
+//!   12 arguments, using the Rust, `"C"` or `"system"` ABI, and for variadic `"C"` function
+//!   pointers. Other ABIs, like `"stdcall"` or `"fastcall"`, aren't covered. Pointers with a
+//!   higher-ranked lifetime, like `fn(&T)`, are only covered when the first argument is the
+//!   borrowed one, through the `higher-ranked-fn-ptrs` feature. It's on by default, but rustc
+//!   warns those impls may stop compiling in future versions, so it can be turned off with
+//!   `default-features = false`.
 
+use std::cell::{Cell, RefCell};
 use std::fmt::{Debug as StdDebug, Error, Result, Write};
 
 mod builders;
+mod bytes;
+mod color;
+mod dbg;
+mod diff;
+mod error;
+mod format;
+mod numbers;
+mod options;
+mod sink;
 mod std_impls;
+mod strings;
+mod value;
 
 pub use builders::{DebugList, DebugMap, DebugSet, DebugStruct, DebugTuple};
+pub use bytes::{Bytes, BytesStyle};
+pub use color::{Theme, TokenKind};
+#[doc(hidden)]
+pub use dbg::{print as __dbg, print_here as __dbg_here};
+pub use dbg::{set_dbg_sink, take_dbg_sink, DbgRecord, DbgSink};
+pub use diff::{diff, diff_with};
+pub use error::ErrorReport;
+#[doc(hidden)]
+pub use format::{Placeholders as __Placeholders, Pretty as __Pretty};
+pub use numbers::{IntStyle, IntStyled};
+pub use options::{with_options, Align, PrettyOptions};
+pub use sink::Sink;
+pub use value::{to_value, Value};
 
 pub use debug2_derive::*;
 
-const MAX_LEN: usize = 80;
 /// Pretty Printed Formatting
 ///
 /// This is much like [`std::fmt::Debug`], but it supports much better multiline output
@@ -189,6 +220,18 @@ pub trait Debug {
     /// side effects in this, make sure they are idempotent. In general, don't relly on how often
     /// this function is called, as it may change in a future release.
     fn fmt(&self, f: &mut Formatter<'_>) -> Result;
+
+    /// Formats a slice of values.
+    ///
+    /// This is used by the implementations for slices and [`Vec`], so that types like `u8` can
+    /// format a collection of themselves differently. The default is a list of entries.
+    #[doc(hidden)]
+    fn fmt_slice(slice: &[Self], f: &mut Formatter<'_>) -> Result
+    where
+        Self: Sized,
+    {
+        f.debug_list().entries(slice.iter()).finish()
+    }
 }
 
 /// Configuration for formatting.
@@ -203,6 +246,44 @@ pub trait Debug {
 pub struct Formatter<'a> {
     buf: &'a mut (dyn Write + 'a),
     mode: Mode,
+    opts: &'a PrettyOptions,
+    // How integers are written, which `IntStyled` can change from
+    // `opts.int_style`.
+    int_style: IntStyle,
+    // How many levels of `PadAdapter` we're behind.
+    indent: usize,
+    // How many builders we're inside of.
+    depth: usize,
+    // Whether any builders have been started, i.e. this isn't a leaf value.
+    nested: bool,
+    // Where to record the fields of the struct being printed, if any.
+    row: Option<&'a RefCell<builders::Row>>,
+    // The width to pad the keys of the next struct or map to.
+    key_width: Option<usize>,
+    // The column the output is at, shared by every `Formatter` writing to it.
+    column: &'a Cell<usize>,
+    // Where to send the structure of the value instead of writing text, see
+    // `visit`.
+    sink: Option<&'a mut (dyn Sink + 'a)>,
+    // The parts of a scalar written so far, which is sent to the sink in one
+    // piece.
+    scalar: Option<(TokenKind, String)>,
+}
+
+/// The root of the output, which keeps track of the current column.
+struct Output<'a> {
+    buf: String,
+    column: &'a Cell<usize>,
+}
+
+impl Write for Output<'_> {
+    fn write_str(&mut self, s: &str) -> Result {
+        match s.rfind('\n') {
+            Some(pos) => self.column.set(text_width(&s[pos + 1..])),
+            None => self.column.set(self.column.get() + text_width(s)),
+        }
+        self.buf.write_str(s)
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -211,18 +292,75 @@ enum Mode {
     Flat,
 }
 
-fn flatprint_checked<T: Debug>(x: T) -> std::result::Result<String, Error> {
-    pprint_mode(x, Mode::Flat)
+fn flatprint_checked<T: Debug>(
+    x: T,
+    opts: &PrettyOptions,
+    depth: usize,
+    int_style: IntStyle,
+) -> std::result::Result<String, Error> {
+    print_mode(x, Mode::Flat, opts, depth, int_style, None, None).map(|(text, _)| text)
 }
 
-fn pprint_mode<T: Debug>(x: T, mode: Mode) -> std::result::Result<String, Error> {
-    let mut out = String::new();
+/// A value printed on one line by [`flatprint_entry`].
+struct FlatEntry {
+    text: String,
+    // Whether no builders were used, so it's a number, string or similar.
+    leaf: bool,
+    // The struct's fields, if it's a single struct.
+    row: Option<builders::Row>,
+    // The width of the widest field name or key, if it's a struct or map.
+    key_width: Option<usize>,
+}
+
+/// Prints an entry of a list on one line, noting what kind of value it is.
+fn flatprint_entry<T: Debug>(
+    x: T,
+    opts: &PrettyOptions,
+    depth: usize,
+    int_style: IntStyle,
+) -> std::result::Result<FlatEntry, Error> {
+    let row = RefCell::new(builders::Row::new(depth));
+    let (text, nested) = print_mode(x, Mode::Flat, opts, depth, int_style, Some(&row), None)?;
+    let row = row.into_inner();
+    Ok(FlatEntry {
+        text,
+        leaf: !nested,
+        key_width: row.key_width,
+        row: row.finish(),
+    })
+}
+
+fn print_mode<T: Debug>(
+    x: T,
+    mode: Mode,
+    opts: &PrettyOptions,
+    depth: usize,
+    int_style: IntStyle,
+    row: Option<&RefCell<builders::Row>>,
+    key_width: Option<usize>,
+) -> std::result::Result<(String, bool), Error> {
+    let column = Cell::new(0);
+    let mut out = Output {
+        buf: String::new(),
+        column: &column,
+    };
     let mut f = Formatter {
         buf: &mut out,
         mode,
+        opts,
+        int_style,
+        indent: 0,
+        depth,
+        nested: false,
+        row,
+        key_width,
+        column: &column,
+        sink: None,
+        scalar: None,
     };
     x.fmt(&mut f)?;
-    Ok(out)
+    let nested = f.nested;
+    Ok((out.buf, nested))
 }
 
 /// Pretty Print an item to a string, or return an error
@@ -250,12 +388,7 @@ fn pprint_mode<T: Debug>(x: T, mode: Mode) -> std::result::Result<String, Error>
 /// assert!(pprint_checked(Bad).is_err());
 /// ```
 pub fn pprint_checked<T: Debug>(x: T) -> std::result::Result<String, Error> {
-    let flat = flatprint_checked(&x)?;
-    if flat.len() <= MAX_LEN {
-        Ok(flat)
-    } else {
-        pprint_mode(x, Mode::Pretty)
-    }
+    pprint_with_checked(x, &PrettyOptions::current())
 }
 
 /// Pretty Print an item to a string
@@ -280,6 +413,10 @@ pub fn pprint_checked<T: Debug>(x: T) -> std::result::Result<String, Error> {
 /// Note that while this takes a `T`, you can also pass a reference due to the
 /// `impl<T: Debug> Debug for `&T`
 ///
+/// The options used can be changed with environment variables, like
+/// `DEBUG2_WIDTH=120` (see [`PrettyOptions::from_env`]), or for a while on one
+/// thread with [`with_options`].
+///
 /// # Panics
 ///
 /// This will panic if `<T as Debug>::fmt` returns an error
@@ -288,15 +425,121 @@ pub fn pprint<T: Debug>(x: T) -> String {
     pprint_checked(x).unwrap()
 }
 
+/// Pretty Print an item to a string using the given options, or return an error
+///
+/// ```rust
+/// use debug2::{pprint_with_checked, PrettyOptions};
+///
+/// let opts = PrettyOptions::new().width(15);
+///
+/// assert_eq!(
+///     pprint_with_checked(vec!["Hello", "World"], &opts),
+///     Ok("[\n    \"Hello\",\n    \"World\",\n]".to_owned())
+/// );
+/// ```
+pub fn pprint_with_checked<T: Debug>(
+    x: T,
+    opts: &PrettyOptions,
+) -> std::result::Result<String, Error> {
+    let flat = flatprint_entry(&x, opts, 0, opts.int_style)?;
+    if text_width(&flat.text) <= opts.width {
+        Ok(flat.text)
+    } else {
+        let key_width = flat.key_width.filter(|_| opts.align_keys);
+        print_mode(x, Mode::Pretty, opts, 0, opts.int_style, None, key_width).map(|(text, _)| text)
+    }
+}
+
+/// Pretty Print an item to a string using the given options
+///
+/// ```rust
+/// use debug2::{pprint_with, BytesStyle, PrettyOptions};
+///
+/// let opts = PrettyOptions::new().bytes(BytesStyle::Escaped);
+///
+/// assert_eq!(pprint_with(b"hi\n", &opts), r#"b"hi\n""#);
+/// ```
+///
+/// # Panics
+///
+/// This will panic if `<T as Debug>::fmt` returns an error
+pub fn pprint_with<T: Debug>(x: T, opts: &PrettyOptions) -> String {
+    pprint_with_checked(x, opts).unwrap()
+}
+
+/// Sends the structure of an item to a [`Sink`], instead of printing it.
+///
+/// See [`Sink`] for an example.
+pub fn visit<T: Debug>(x: T, sink: &mut dyn Sink) -> Result {
+    visit_with(x, &PrettyOptions::default(), sink)
+}
+
+/// Sends the structure of an item to a [`Sink`] using the given options.
+///
+/// The options change how scalars are written, and which parts of the value
+/// are left out.
+pub fn visit_with<T: Debug>(x: T, opts: &PrettyOptions, sink: &mut dyn Sink) -> Result {
+    let column = Cell::new(0);
+    let mut buf = String::new();
+    let mut f = Formatter {
+        buf: &mut buf,
+        mode: Mode::Flat,
+        opts,
+        int_style: opts.int_style,
+        indent: 0,
+        depth: 0,
+        nested: false,
+        row: None,
+        key_width: None,
+        column: &column,
+        sink: Some(sink),
+        scalar: None,
+    };
+    x.fmt(&mut f)?;
+    f.end_scalar()
+}
+
 impl<'a> Formatter<'a> {
     fn write_debug<T: StdDebug>(&mut self, val: &T) -> Result {
-        write!(self.buf, "{:?}", val)
+        self.write_token(TokenKind::Other, &format!("{:?}", val))
     }
 
     fn write_str(&mut self, data: &str) -> Result {
+        if self.sink.is_some() {
+            return self.write_token(TokenKind::Other, data);
+        }
         self.buf.write_str(data)
     }
 
+    /// Writes `text`, colored as `kind` if the options have a theme.
+    fn write_token(&mut self, kind: TokenKind, text: &str) -> Result {
+        if self.sink.is_some() {
+            let (_, scalar) = self.scalar.get_or_insert_with(|| (kind, String::new()));
+            scalar.push_str(text);
+            return Ok(());
+        }
+        let opts = self.opts;
+        self.buf.write_str(&opts.paint(kind, text))
+    }
+
+    /// Sends the scalar written so far to the sink, if there is one. This is
+    /// called after each value, so the sink gets each scalar in one piece.
+    fn end_scalar(&mut self) -> Result {
+        match (self.scalar.take(), self.sink()) {
+            (Some((kind, text)), Some(sink)) => sink.scalar(kind, &text),
+            _ => Ok(()),
+        }
+    }
+
+    /// The sink to send the structure of the value to, if there is one.
+    fn sink(&mut self) -> Option<&mut (dyn Sink + 'a)> {
+        self.sink.as_deref_mut()
+    }
+
+    fn write_punct(&mut self, text: &str) -> Result {
+        self.write_token(TokenKind::Punct, text)
+    }
+
     /// Creates a [`DebugStruct`] builder designed to assist with creation of
     /// [`Debug`] implementations for structs.
     ///
@@ -319,17 +562,13 @@ impl<'a> Formatter<'a> {
     ///         fmt.debug_struct("Foo")
     ///             .field("bar", &self.bar)
     ///             .field("baz", &self.baz)
-    /// # // TODO: The viersion in `std` uses `format_args`, which gives
-    /// # // a different result, because it doesn't have "" around the content
-    /// # // I should have a macro that delegated to `format!`, but returns a
-    /// # // newtype that doesnt add quotes
-    ///             .field("addr", &format!("{}", self.addr))
+    ///             .field("addr", &self.addr)
     ///             .finish()
     ///     }
     /// }
     ///
     /// assert_eq!(
-    ///     "Foo { bar: 10, baz: \"Hello World\", addr: \"127.0.0.1\" }",
+    ///     "Foo { bar: 10, baz: \"Hello World\", addr: 127.0.0.1 }",
     ///     pprint(Foo {
     ///         bar: 10,
     ///         baz: "Hello World".to_string(),
@@ -458,8 +697,17 @@ impl<'a> Formatter<'a> {
             buf: wrap(self.buf),
 
             // And preserve these
-            mode: self.mode
-
+            mode: self.mode,
+            opts: self.opts,
+            int_style: self.int_style,
+            indent: self.indent,
+            depth: self.depth,
+            nested: self.nested,
+            row: self.row,
+            key_width: None,
+            column: self.column,
+            sink: self.sink.as_mut().map(|sink| &mut **sink as &mut dyn Sink),
+            scalar: None,
             // flags: self.flags,
             // fill: self.fill,
             // align: self.align,
@@ -471,39 +719,369 @@ impl<'a> Formatter<'a> {
     fn is_pretty(&self) -> bool {
         self.mode == Mode::Pretty
     }
+
+    /// Whether builders created now should hide their contents.
+    fn is_too_deep(&self) -> bool {
+        self.opts.max_depth.is_some_and(|max| self.depth >= max)
+    }
+
+    /// The width available to a line at the current indentation.
+    fn remaining_width(&self) -> usize {
+        let indent = self.indent * self.opts.indent_width();
+        self.opts.width.saturating_sub(indent)
+    }
+
+    /// The width left on the current line.
+    fn remaining_on_line(&self) -> usize {
+        // At the start of a line, the indentation hasn't been written yet.
+        let column = self
+            .column
+            .get()
+            .max(self.indent * self.opts.indent_width());
+        self.opts.width.saturating_sub(column)
+    }
+}
+
+/// How many columns `s` takes up, counting tabs as 4 and ignoring escape
+/// sequences.
+fn text_width(s: &str) -> usize {
+    let mut width = 0;
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            // Colors don't take up any space.
+            '\x1b' => color::skip_escape(&mut chars),
+            '\t' => width += 4,
+            _ => width += 1,
+        }
+    }
+    width
+}
+
+/// Formats a number with `_` between every 3 digits, like `1_000_000`.
+fn group_digits(n: usize) -> String {
+    numbers::group(&n.to_string(), 3)
 }
 
 /// Prints and returns the value of a given expression for quick and dirty debugging.
 ///
 /// Like [`std::dbg`], but used [`crate::Debug`] instead of [`std::fmt::Debug`]
 ///
+/// Values split over several lines are lined up under where they start, after
+/// the `[file:line] expr = ` prefix. Given several expressions, each is printed
+/// in turn, and they're returned as a tuple.
+///
+/// ```rust
+/// use debug2::dbg;
+///
+/// let a = 2;
+/// let (b, c) = dbg!(a * 2, a + 1);
+/// assert_eq!((b, c), (4, 3));
+/// ```
+///
+/// Output can be sent somewhere other than stderr with [`set_dbg_sink`].
+/// Setting the `DEBUG2_DBG` environment variable to a comma separated list of
+/// module paths only prints in those modules and the modules inside them, and
+/// paths starting with `-` are silenced, so `DEBUG2_DBG=-my_crate::parser`
+/// prints everywhere but the parser. It's read the first time `dbg!` is used.
+///
 /// # Stability
 ///
 /// The exact output printed by this macro should not be relied upon and is subject to future changes.
 ///
 /// # Panics
 ///
-/// Panics if writing to [`std::io::stderr`] fails.
+/// Panics if writing to [`std::io::stderr`] fails, or if the sink panics.
 #[macro_export]
 macro_rules! dbg {
     () => {
-        eprintln!("[{}:{}]", file!(), line!())
+        $crate::__dbg_here(
+            ::std::file!(),
+            ::std::line!(),
+            ::std::column!(),
+            ::std::module_path!(),
+        )
     };
     ($val:expr $(,)?) => {
         match $val {
             tmp => {
-                eprintln!(
-                    "[{}:{}] {} = {}",
-                    file!(),
-                    line!(),
-                    stringify!($val),
-                    $crate::pprint(&tmp)
+                $crate::__dbg(
+                    ::std::file!(),
+                    ::std::line!(),
+                    ::std::column!(),
+                    ::std::module_path!(),
+                    ::std::stringify!($val),
+                    &tmp,
                 );
                 tmp
             }
         }
     };
     ($($val:expr),+ $(,)?) => {
-        $crate::dbg!($($val:expr),+)
+        ($($crate::dbg!($val)),+,)
     };
 }
+
+/// Creates a `String` like [`std::format`], but with the arguments formatted
+/// using [`Debug`] and [`pprint`].
+///
+/// When a value is split over several lines, the lines after the first are
+/// indented to line up with where it started, and it's laid out to fit in
+/// what's left of the line.
+///
+/// Only arguments given after the format string are pretty printed, so
+/// `{x}` where `x` is a variable in scope is formatted with [`std::fmt::Display`].
+///
+/// # Examples
+///
+/// ```rust
+/// use debug2::pformat;
+///
+/// assert_eq!(pformat!("{} and {}", Some(1), "two"), "Some(1) and \"two\"");
+///
+/// let grid = vec![vec![0; 12]; 3];
+/// assert_eq!(
+///     pformat!("grid = {} // {rows} rows", grid, rows = grid.len()),
+///     "\
+/// grid = [
+///            [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+///            [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+///            [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+///        ] // 3 rows"
+/// );
+/// ```
+#[macro_export]
+macro_rules! pformat {
+    ($fmt:literal $(,)?) => {
+        ::std::format!($fmt)
+    };
+    ($fmt:literal, $($args:tt)+) => {{
+        let placeholders = $crate::__Placeholders::default();
+        $crate::__pformat!(placeholders [$fmt] [] $($args)+)
+    }};
+}
+
+/// Wraps each argument of `pformat!` so it's pretty printed.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __pformat {
+    ($placeholders:ident [$fmt:literal] [$($done:tt)*]) => {
+        $placeholders.format(::std::format_args!($fmt $($done)*))
+    };
+    ($placeholders:ident [$fmt:literal] [$($done:tt)*] $name:ident = $arg:expr $(, $($rest:tt)*)?) => {
+        $crate::__pformat!(
+            $placeholders [$fmt]
+            [$($done)*, $name = $crate::__Pretty::new(&$placeholders, &$arg)]
+            $($($rest)*)?
+        )
+    };
+    ($placeholders:ident [$fmt:literal] [$($done:tt)*] $arg:expr $(, $($rest:tt)*)?) => {
+        $crate::__pformat!(
+            $placeholders [$fmt]
+            [$($done)*, $crate::__Pretty::new(&$placeholders, &$arg)]
+            $($($rest)*)?
+        )
+    };
+}
+
+/// Prints to the standard output, with a newline, like [`std::println`], but
+/// with the arguments formatted like [`pformat!`].
+///
+/// # Examples
+///
+/// ```rust
+/// use debug2::pprintln;
+///
+/// pprintln!("state = {}", vec![Some(1), None]);
+/// ```
+///
+/// # Panics
+///
+/// Panics if writing to [`std::io::stdout`] fails.
+#[macro_export]
+macro_rules! pprintln {
+    () => {
+        ::std::println!()
+    };
+    ($($args:tt)+) => {
+        ::std::println!("{}", $crate::pformat!($($args)+))
+    };
+}
+
+/// Prints to the standard error, with a newline, like [`std::eprintln`], but
+/// with the arguments formatted like [`pformat!`].
+///
+/// # Examples
+///
+/// ```rust
+/// use debug2::peprintln;
+///
+/// peprintln!("error: unexpected {}", (1, "one"));
+/// ```
+///
+/// # Panics
+///
+/// Panics if writing to [`std::io::stderr`] fails.
+#[macro_export]
+macro_rules! peprintln {
+    () => {
+        ::std::eprintln!()
+    };
+    ($($args:tt)+) => {
+        ::std::eprintln!("{}", $crate::pformat!($($args)+))
+    };
+}
+
+/// Asserts that two expressions are equal to each other (using [`PartialEq`]).
+///
+/// Like [`std::assert_eq`], but on failure both values are shown using
+/// [`pprint`], along with a [`diff`] of where they differ.
+///
+/// # Examples
+///
+/// ```rust
+/// use debug2::assert_eq;
+///
+/// let a = vec![1, 2, 3];
+/// let b = vec![1, 2, 3];
+///
+/// assert_eq!(a, b);
+/// assert_eq!(a, b, "we are testing addition with {} and {}", 1, 2);
+/// ```
+///
+/// ```rust,should_panic
+/// use debug2::assert_eq;
+///
+/// assert_eq!(vec![1, 2, 3], vec![1, 2, 4]);
+/// // panics with:
+/// // assertion `left == right` failed
+/// //  left: [1, 2, 3]
+/// // right: [1, 2, 4]
+/// // diff (- left, + right):
+/// //  [
+/// //      ..
+/// // -    3,
+/// // +    4,
+/// //  ]
+/// ```
+#[macro_export]
+macro_rules! assert_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left_val, right_val) => {
+                if !(*left_val == *right_val) {
+                    $crate::__assert_failed("==", &*left_val, &*right_val, ::std::option::Option::None);
+                }
+            }
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        match (&$left, &$right) {
+            (left_val, right_val) => {
+                if !(*left_val == *right_val) {
+                    $crate::__assert_failed(
+                        "==",
+                        &*left_val,
+                        &*right_val,
+                        ::std::option::Option::Some(::std::format_args!($($arg)+)),
+                    );
+                }
+            }
+        }
+    };
+}
+
+/// Asserts that two expressions are not equal to each other (using
+/// [`PartialEq`]).
+///
+/// Like [`std::assert_ne`], but on failure both values are shown using
+/// [`pprint`].
+///
+/// # Examples
+///
+/// ```rust
+/// use debug2::assert_ne;
+///
+/// let a = vec![1, 2, 3];
+/// let b = vec![1, 2, 4];
+///
+/// assert_ne!(a, b);
+/// assert_ne!(a, b, "we are testing that the values are not equal");
+/// ```
+#[macro_export]
+macro_rules! assert_ne {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left_val, right_val) => {
+                if *left_val == *right_val {
+                    $crate::__assert_failed("!=", &*left_val, &*right_val, ::std::option::Option::None);
+                }
+            }
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        match (&$left, &$right) {
+            (left_val, right_val) => {
+                if *left_val == *right_val {
+                    $crate::__assert_failed(
+                        "!=",
+                        &*left_val,
+                        &*right_val,
+                        ::std::option::Option::Some(::std::format_args!($($arg)+)),
+                    );
+                }
+            }
+        }
+    };
+}
+
+/// Panics with the message for a failed [`assert_eq!`] or [`assert_ne!`].
+#[doc(hidden)]
+#[track_caller]
+pub fn __assert_failed<L, R>(
+    op: &str,
+    left: &L,
+    right: &R,
+    args: Option<std::fmt::Arguments<'_>>,
+) -> !
+where
+    L: Debug + ?Sized,
+    R: Debug + ?Sized,
+{
+    // The sides and the diff are all written with the same options, so they
+    // agree with each other.
+    let opts = PrettyOptions::current();
+    let mut message = format!("assertion `left {} right` failed", op);
+    if let Some(args) = args {
+        message.push_str(&format!(": {}", args));
+    }
+    write_side(&mut message, " left", left, &opts);
+    write_side(&mut message, "right", right, &opts);
+    if op == "==" {
+        let diff = diff_with(left, right, &opts);
+        if diff.is_empty() {
+            // Like a NaN, or a `PartialEq` that doesn't match `Debug`.
+            message.push_str("\nnote: the values are formatted the same, but aren't equal");
+        } else {
+            message.push_str("\ndiff (- left, + right):\n");
+            message.push_str(&diff);
+        }
+    }
+    panic!("{}", message)
+}
+
+/// Writes one side of a failed assertion, starting on its own line if it
+/// doesn't fit on one.
+fn write_side<T: Debug + ?Sized>(
+    message: &mut String,
+    name: &str,
+    value: &T,
+    opts: &PrettyOptions,
+) {
+    let text = pprint_with(value, opts);
+    if text.contains('\n') {
+        message.push_str(&format!("\n{}:\n{}", name.trim_start(), text));
+    } else {
+        message.push_str(&format!("\n{}: {}", name, text));
+    }
+}
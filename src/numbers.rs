@@ -0,0 +1,149 @@
+use std::fmt::{Binary, Display, LowerHex, Octal};
+
+use crate::{Debug, Formatter, Result, TokenKind};
+
+/// How integers are shown.
+///
+/// This is set with [`PrettyOptions::int_style`](crate::PrettyOptions::int_style),
+/// or for a single value with [`IntStyled`].
+///
+/// # Examples
+///
+/// ```rust
+/// use debug2::{pprint_with, IntStyle, PrettyOptions};
+///
+/// let style = |style| pprint_with(1_234_567u32, &PrettyOptions::new().int_style(style));
+///
+/// assert_eq!(style(IntStyle::Decimal), "1234567");
+/// assert_eq!(style(IntStyle::Grouped), "1_234_567");
+/// assert_eq!(style(IntStyle::Hex), "0x12_d687");
+/// assert_eq!(style(IntStyle::Binary), "0b1_0010_1101_0110_1000_0111");
+/// assert_eq!(style(IntStyle::Octal), "0o4553207");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, std::fmt::Debug)]
+pub enum IntStyle {
+    /// Plain decimal: `1234567`.
+    Decimal,
+    /// Decimal with `_` between every 3 digits: `1_234_567`.
+    Grouped,
+    /// Hexadecimal with `_` between every 4 digits: `0x12_d687`.
+    ///
+    /// Negative numbers are shown in two's complement, like `{:x}`.
+    Hex,
+    /// Binary with `_` between every 4 digits: `0b1_0010_1101`.
+    Binary,
+    /// Octal: `0o4553207`.
+    Octal,
+}
+
+/// Shows integers in `value` in the given style, whatever the options say.
+///
+/// This is what `#[debug2(hex)]` and friends use when deriving [`Debug`].
+///
+/// # Examples
+///
+/// ```rust
+/// use debug2::{pprint, Debug, Formatter, IntStyle, IntStyled};
+/// use std::fmt;
+///
+/// struct Register {
+///     name: &'static str,
+///     value: u32,
+/// }
+///
+/// impl Debug for Register {
+///     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+///         f.debug_struct("Register")
+///             .field("name", &self.name)
+///             .field("value", &IntStyled(&self.value, IntStyle::Hex))
+///             .finish()
+///     }
+/// }
+///
+/// assert_eq!(
+///     pprint(Register { name: "status", value: 0xff }),
+///     "Register { name: \"status\", value: 0xff }"
+/// );
+/// ```
+pub struct IntStyled<'a, T: ?Sized>(pub &'a T, pub IntStyle);
+
+impl<T: ?Sized + Debug> Debug for IntStyled<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let outer = std::mem::replace(&mut f.int_style, self.1);
+        let result = self.0.fmt(f);
+        f.int_style = outer;
+        result
+    }
+}
+
+pub(crate) trait Int: Copy + Display + LowerHex + Binary + Octal {
+    const BITS: usize;
+}
+
+macro_rules! int {
+    ($($t:ty),+) => {
+        $(
+            impl Int for $t {
+                const BITS: usize = std::mem::size_of::<$t>() * 8;
+            }
+        )+
+    };
+}
+
+int! {
+    i8, i16, i32, i64, i128, isize,
+    u8, u16, u32, u64, u128, usize
+}
+
+pub(crate) fn fmt_int<T: Int>(n: T, f: &mut Formatter<'_>) -> Result {
+    let pad = f.opts.pad_ints;
+    let text = match f.int_style {
+        IntStyle::Decimal => n.to_string(),
+        IntStyle::Grouped => {
+            let digits = n.to_string();
+            match digits.strip_prefix('-') {
+                Some(digits) => format!("-{}", group(digits, 3)),
+                None => group(&digits, 3),
+            }
+        }
+        IntStyle::Hex => radix("0x", &format!("{:x}", n), pad, T::BITS / 4, 4),
+        IntStyle::Binary => radix("0b", &format!("{:b}", n), pad, T::BITS, 4),
+        IntStyle::Octal => radix("0o", &format!("{:o}", n), pad, (T::BITS + 2) / 3, 0),
+    };
+    f.write_token(TokenKind::Number, &text)
+}
+
+/// Writes `digits` with a prefix, padded with zeros to `width` if `pad`, and
+/// grouped by `group_size` if it isn't 0.
+fn radix(prefix: &str, digits: &str, pad: bool, width: usize, group_size: usize) -> String {
+    let zeros = if pad {
+        width.saturating_sub(digits.len())
+    } else {
+        0
+    };
+    let digits = format!("{}{}", "0".repeat(zeros), digits);
+    if group_size == 0 {
+        format!("{}{}", prefix, digits)
+    } else {
+        format!("{}{}", prefix, group(&digits, group_size))
+    }
+}
+
+/// Puts `_` between every `size` digits, counting from the right.
+pub(crate) fn group(digits: &str, size: usize) -> String {
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / size);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % size == 0 {
+            grouped.push('_');
+        }
+        grouped.push(c);
+    }
+    grouped
+}
+
+pub(crate) fn fmt_float<T: std::fmt::Debug>(n: T, f: &mut Formatter<'_>) -> Result {
+    match f.opts.float_precision {
+        Some(precision) => f.write_token(TokenKind::Number, &format!("{:.*?}", precision, n)),
+        None => f.write_token(TokenKind::Number, &format!("{:?}", n)),
+    }
+}
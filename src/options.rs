@@ -0,0 +1,672 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::env;
+use std::io::{self, IsTerminal};
+use std::sync::OnceLock;
+
+use crate::{BytesStyle, IntStyle, Theme, TokenKind};
+
+/// Options that control how values are laid out.
+///
+/// These are passed to [`pprint_with`](crate::pprint_with).
+///
+/// # Examples
+///
+/// ```rust
+/// use debug2::{pprint_with, PrettyOptions};
+///
+/// let opts = PrettyOptions::new().width(10);
+///
+/// assert_eq!(
+///     pprint_with(vec![1, 2, 3, 4], &opts),
+///     "\
+/// [
+///     1, 2,
+///     3, 4,
+/// ]"
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrettyOptions {
+    pub(crate) width: usize,
+    pub(crate) bytes: BytesStyle,
+    pub(crate) int_style: IntStyle,
+    pub(crate) pad_ints: bool,
+    pub(crate) float_precision: Option<usize>,
+    pub(crate) theme: Option<Theme>,
+    pub(crate) max_items: Option<usize>,
+    pub(crate) max_depth: Option<usize>,
+    pub(crate) max_string_len: Option<usize>,
+    pub(crate) wrap_strings: bool,
+    pub(crate) indent: String,
+    pub(crate) fill: bool,
+    pub(crate) align: Align,
+    pub(crate) align_keys: bool,
+    pub(crate) sort_unordered: bool,
+    pub(crate) trailing_comma: bool,
+    pub(crate) space_inside_braces: bool,
+    pub(crate) field_separator: String,
+}
+
+thread_local! {
+    // The options set with `with_options`, innermost last.
+    static SCOPED: RefCell<Vec<PrettyOptions>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Runs `f`, using `opts` in place of the defaults for anything it prints on
+/// this thread.
+///
+/// This changes the options used by [`pprint`](crate::pprint),
+/// [`pprint_checked`](crate::pprint_checked), [`dbg!`](crate::dbg) and
+/// [`pformat!`](crate::pformat), so code that can't be given options can still
+/// be changed. The options are used whole, rather than on top of any set by an
+/// outer call, and the ones before are put back when `f` returns or panics.
+///
+/// ```rust
+/// use debug2::{pprint, with_options, PrettyOptions};
+///
+/// let opts = PrettyOptions::new().max_items(2);
+///
+/// assert_eq!(with_options(opts, || pprint(vec![1, 2, 3])), "[1, 2, ... 1 more]");
+/// assert_eq!(pprint(vec![1, 2, 3]), "[1, 2, 3]");
+/// ```
+pub fn with_options<R>(opts: PrettyOptions, f: impl FnOnce() -> R) -> R {
+    struct Pop;
+
+    impl Drop for Pop {
+        fn drop(&mut self) {
+            SCOPED.with(|scoped| scoped.borrow_mut().pop());
+        }
+    }
+
+    SCOPED.with(|scoped| scoped.borrow_mut().push(opts));
+    let _pop = Pop;
+    f()
+}
+
+impl Default for PrettyOptions {
+    fn default() -> Self {
+        PrettyOptions {
+            width: 80,
+            bytes: BytesStyle::List,
+            int_style: IntStyle::Decimal,
+            pad_ints: false,
+            float_precision: None,
+            theme: None,
+            max_items: None,
+            max_depth: None,
+            max_string_len: None,
+            wrap_strings: false,
+            indent: "    ".to_owned(),
+            fill: true,
+            align: Align::Off,
+            align_keys: false,
+            sort_unordered: false,
+            trailing_comma: true,
+            space_inside_braces: true,
+            field_separator: ": ".to_owned(),
+        }
+    }
+}
+
+impl PrettyOptions {
+    /// Creates the default options.
+    ///
+    /// [`pprint`](crate::pprint) uses these, changed by the environment as
+    /// described in [`from_env`](Self::from_env), unless they're replaced with
+    /// [`with_options`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates the default options, changed by any of these environment
+    /// variables that are set:
+    ///
+    /// - `DEBUG2_WIDTH` sets the [`width`](Self::width). If it's `auto`, the
+    ///   width of the terminal is taken from `COLUMNS`.
+    /// - `DEBUG2_COLOR` sets whether the output is [colored](Self::color). It
+    ///   can be `always`, `never`, or `auto` to color the output when stderr
+    ///   is a terminal and `NO_COLOR` isn't set.
+    /// - `DEBUG2_MAX_DEPTH` sets the [`max_depth`](Self::max_depth).
+    /// - `DEBUG2_MAX_ITEMS` sets the [`max_items`](Self::max_items).
+    ///
+    /// Values that can't be understood are ignored. The variables are read
+    /// once, the first time this is called, so changing them afterwards has
+    /// no effect.
+    ///
+    /// These are the options used by [`pprint`](crate::pprint),
+    /// [`pprint_checked`](crate::pprint_checked), [`dbg!`](crate::dbg) and
+    /// [`pformat!`](crate::pformat), unless they're replaced with
+    /// [`with_options`].
+    pub fn from_env() -> Self {
+        static FROM_ENV: OnceLock<PrettyOptions> = OnceLock::new();
+        FROM_ENV
+            .get_or_init(|| Self::from_vars(|name| env::var(name).ok()))
+            .clone()
+    }
+
+    /// The options set by the innermost [`with_options`] on this thread, or
+    /// else [`from_env`](Self::from_env).
+    pub(crate) fn current() -> Self {
+        SCOPED
+            .with(|scoped| scoped.borrow().last().cloned())
+            .unwrap_or_else(Self::from_env)
+    }
+
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Self {
+        let number = |name| var(name)?.trim().parse::<usize>().ok();
+        let mut opts = Self::default();
+
+        let width = match var("DEBUG2_WIDTH").as_deref().map(str::trim) {
+            Some("auto") => number("COLUMNS"),
+            Some(width) => width.parse().ok(),
+            None => None,
+        };
+        if let Some(width) = width {
+            opts.width = width;
+        }
+
+        match var("DEBUG2_COLOR").as_deref().map(str::trim) {
+            Some("always") => opts = opts.color(true),
+            Some("never") => opts = opts.color(false),
+            Some("auto") => {
+                let color =
+                    var("NO_COLOR").map_or(true, |no| no.is_empty()) && io::stderr().is_terminal();
+                opts = opts.color(color);
+            }
+            _ => {}
+        }
+
+        if let Some(max) = number("DEBUG2_MAX_DEPTH") {
+            opts.max_depth = Some(max);
+        }
+        if let Some(max) = number("DEBUG2_MAX_ITEMS") {
+            opts.max_items = Some(max);
+        }
+        opts
+    }
+
+    /// Sets the maximum line width to aim for.
+    ///
+    /// Values that are longer than this when written on one line are split
+    /// over multiple lines. Defaults to 80.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets how byte slices (`[u8]`, `Vec<u8>`, and [`Bytes`](crate::Bytes)) are shown.
+    ///
+    /// Defaults to [`BytesStyle::List`], which shows them like any other list.
+    pub fn bytes(mut self, style: BytesStyle) -> Self {
+        self.bytes = style;
+        self
+    }
+
+    /// Sets how integers are shown.
+    ///
+    /// Defaults to [`IntStyle::Decimal`]. This can be overridden for a single
+    /// value with [`IntStyled`](crate::IntStyled), or a field with
+    /// `#[debug2(hex)]` and friends when deriving [`Debug`](crate::Debug).
+    ///
+    /// ```rust
+    /// use debug2::{pprint_with, IntStyle, PrettyOptions};
+    ///
+    /// let opts = PrettyOptions::new().int_style(IntStyle::Hex);
+    ///
+    /// assert_eq!(pprint_with(vec![10u8, 255, 16], &opts), "[0xa, 0xff, 0x10]");
+    /// ```
+    pub fn int_style(mut self, style: IntStyle) -> Self {
+        self.int_style = style;
+        self
+    }
+
+    /// Sets whether hex, binary and octal integers are padded with zeros to
+    /// the width of their type.
+    ///
+    /// Defaults to `false`.
+    ///
+    /// ```rust
+    /// use debug2::{pprint_with, IntStyle, PrettyOptions};
+    ///
+    /// let opts = PrettyOptions::new().int_style(IntStyle::Hex).pad_ints(true);
+    ///
+    /// assert_eq!(pprint_with(0xffu32, &opts), "0x0000_00ff");
+    /// assert_eq!(pprint_with(-1i8, &opts), "0xff");
+    /// ```
+    pub fn pad_ints(mut self, pad: bool) -> Self {
+        self.pad_ints = pad;
+        self
+    }
+
+    /// Sets how many digits are shown after the decimal point for `f32` and
+    /// `f64`.
+    ///
+    /// By default, floats are shown with as many digits as it takes to tell
+    /// them apart, like `{:?}`.
+    ///
+    /// ```rust
+    /// use debug2::{pprint_with, PrettyOptions};
+    ///
+    /// let opts = PrettyOptions::new().float_precision(2);
+    ///
+    /// assert_eq!(pprint_with((1.0f32, 2.0f64 / 3.0), &opts), "(1.00, 0.67)");
+    /// ```
+    pub fn float_precision(mut self, precision: usize) -> Self {
+        self.float_precision = Some(precision);
+        self
+    }
+
+    /// Sets the maximum number of entries shown for lists, sets and maps.
+    ///
+    /// Entries past this aren't formatted, and are replaced by a count of how
+    /// many were left out. If the entries come from an iterator that doesn't
+    /// know how many it has left, at most 10,000 are counted, and a count of
+    /// `10_000+` means there were more. By default, all entries are shown.
+    ///
+    /// This can be overridden for a single collection with
+    /// [`DebugList::limit`](crate::DebugList::limit) and friends. It doesn't
+    /// apply to byte slices shown with a [`BytesStyle`] other than `List`.
+    ///
+    /// ```rust
+    /// use debug2::{pprint_with, PrettyOptions};
+    ///
+    /// let opts = PrettyOptions::new().max_items(3);
+    ///
+    /// assert_eq!(
+    ///     pprint_with(vec![1; 1_000_000], &opts),
+    ///     "[1, 1, 1, ... 999_997 more]"
+    /// );
+    /// ```
+    pub fn max_items(mut self, max: usize) -> Self {
+        self.max_items = Some(max);
+        self
+    }
+
+    /// Sets the maximum depth of nested structs, tuples and collections to show.
+    ///
+    /// Anything nested deeper than this has its contents replaced by `..`. By
+    /// default, everything is shown.
+    ///
+    /// ```rust
+    /// use debug2::{pprint_with, PrettyOptions};
+    ///
+    /// let opts = PrettyOptions::new().max_depth(2);
+    ///
+    /// assert_eq!(
+    ///     pprint_with(vec![Some(vec![1]), None, Some(vec![])], &opts),
+    ///     "[Some([..]), None, Some([])]"
+    /// );
+    /// ```
+    pub fn max_depth(mut self, max: usize) -> Self {
+        self.max_depth = Some(max);
+        self
+    }
+
+    /// Sets the maximum number of bytes of a string to show.
+    ///
+    /// Longer strings are cut off (at a character boundary), followed by the
+    /// number of bytes left out. By default, all of the string is shown.
+    ///
+    /// ```rust
+    /// use debug2::{pprint_with, PrettyOptions};
+    ///
+    /// let opts = PrettyOptions::new().max_string_len(6);
+    ///
+    /// assert_eq!(
+    ///     pprint_with("SELECT * FROM users", &opts),
+    ///     r#""SELECT" (13 more bytes)"#
+    /// );
+    /// ```
+    pub fn max_string_len(mut self, max: usize) -> Self {
+        self.max_string_len = Some(max);
+        self
+    }
+
+    /// Sets whether strings that don't fit on a line are split over multiple lines.
+    ///
+    /// Strings are split between words, with an escaped newline (`\`) at the
+    /// end of each line, so the output is still a valid string literal.
+    /// Defaults to `false`.
+    ///
+    /// ```rust
+    /// use debug2::{pprint_with, PrettyOptions};
+    ///
+    /// let opts = PrettyOptions::new().width(30).wrap_strings(true);
+    ///
+    /// assert_eq!(
+    ///     pprint_with("SELECT * FROM users WHERE id = 5 AND name = 'Bob'", &opts),
+    ///     "\
+    /// \"SELECT * FROM users WHERE \\
+    ///  id = 5 AND name = 'Bob'\""
+    /// );
+    /// ```
+    pub fn wrap_strings(mut self, wrap: bool) -> Self {
+        self.wrap_strings = wrap;
+        self
+    }
+
+    /// Sets the string used for each level of indentation.
+    ///
+    /// Defaults to four spaces. Tabs are counted as 4 columns wide when
+    /// working out what fits on a line.
+    ///
+    /// ```rust
+    /// use debug2::{pprint_with, PrettyOptions};
+    ///
+    /// let opts = PrettyOptions::new().width(10).indent("│ ");
+    ///
+    /// assert_eq!(
+    ///     pprint_with(vec![vec![1, 2], vec![3, 4, 5, 6]], &opts),
+    ///     "\
+    /// [
+    /// │ [1, 2],
+    /// │ [
+    /// │ │ 3, 4,
+    /// │ │ 5, 6,
+    /// │ ],
+    /// ]"
+    /// );
+    /// ```
+    pub fn indent(mut self, indent: impl Into<String>) -> Self {
+        self.indent = indent.into();
+        self
+    }
+
+    /// Sets the indentation to the given number of spaces.
+    ///
+    /// This is a shorthand for [`indent`](Self::indent).
+    ///
+    /// ```rust
+    /// use debug2::{pprint_with, PrettyOptions};
+    ///
+    /// let opts = PrettyOptions::new().width(10).indent_spaces(2);
+    ///
+    /// assert_eq!(pprint_with(vec![100, 200, 300], &opts), "[\n  100,\n  200,\n  300,\n]");
+    /// ```
+    pub fn indent_spaces(self, spaces: usize) -> Self {
+        self.indent(" ".repeat(spaces))
+    }
+
+    /// Sets whether lists and sets of leaf values, like numbers and strings,
+    /// are packed as many to a line as fit when split over multiple lines.
+    ///
+    /// If any entry isn't a leaf, every entry is put on its own line instead.
+    /// Defaults to `true`.
+    ///
+    /// ```rust
+    /// use debug2::{pprint_with, PrettyOptions};
+    ///
+    /// let numbers: Vec<u32> = (1..=12).map(|n| n * n).collect();
+    ///
+    /// assert_eq!(
+    ///     pprint_with(&numbers, &PrettyOptions::new().width(30)),
+    ///     "\
+    /// [
+    ///     1, 4, 9, 16, 25, 36, 49,
+    ///     64, 81, 100, 121, 144,
+    /// ]"
+    /// );
+    ///
+    /// assert_eq!(
+    ///     pprint_with(&numbers[..4], &PrettyOptions::new().width(10).fill(false)),
+    ///     "\
+    /// [
+    ///     1,
+    ///     4,
+    ///     9,
+    ///     16,
+    /// ]"
+    /// );
+    /// ```
+    pub fn fill(mut self, fill: bool) -> Self {
+        self.fill = fill;
+        self
+    }
+
+    /// Sets how lists and sets of structs are laid out when split over
+    /// multiple lines.
+    ///
+    /// This only applies when every entry is a struct with the same fields,
+    /// and each fits on a line. Defaults to [`Align::Off`].
+    ///
+    /// ```rust
+    /// use debug2::{pprint_with, Align, Debug, PrettyOptions};
+    ///
+    /// #[derive(Debug)]
+    /// struct Point {
+    ///     x: i32,
+    ///     y: i32,
+    ///     z: i32,
+    /// }
+    ///
+    /// let points = vec![
+    ///     Point { x: 1, y: 22, z: 3 },
+    ///     Point { x: 10, y: 2, z: 33 },
+    /// ];
+    ///
+    /// assert_eq!(
+    ///     pprint_with(&points, &PrettyOptions::new().width(40).align(Align::Fields)),
+    ///     "\
+    /// [
+    ///     Point { x: 1,  y: 22, z: 3 },
+    ///     Point { x: 10, y: 2,  z: 33 },
+    /// ]"
+    /// );
+    ///
+    /// assert_eq!(
+    ///     pprint_with(&points, &PrettyOptions::new().width(40).align(Align::Table)),
+    ///     "\
+    /// [
+    ///     x  | y  | z
+    ///     ---+----+---
+    ///     1  | 22 | 3
+    ///     10 | 2  | 33
+    /// ]"
+    /// );
+    /// ```
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Sets whether the values of a struct or map split over multiple lines
+    /// start in the same column.
+    ///
+    /// Field names and keys are padded to the widest one in the same struct
+    /// or map. Keys whose values are split over several lines themselves
+    /// aren't padded, so those values don't start after a gap. Defaults to
+    /// `false`.
+    ///
+    /// ```rust
+    /// use debug2::{pprint_with, Debug, PrettyOptions};
+    ///
+    /// #[derive(Debug)]
+    /// struct Config {
+    ///     name: &'static str,
+    ///     max_connections: u32,
+    ///     timeout: u32,
+    /// }
+    ///
+    /// let config = Config {
+    ///     name: "production",
+    ///     max_connections: 100,
+    ///     timeout: 30,
+    /// };
+    ///
+    /// assert_eq!(
+    ///     pprint_with(&config, &PrettyOptions::new().width(40).align_keys(true)),
+    ///     "\
+    /// Config {
+    ///     name:            \"production\",
+    ///     max_connections: 100,
+    ///     timeout:         30,
+    /// }"
+    /// );
+    ///
+    /// let opts = PrettyOptions::new()
+    ///     .width(40)
+    ///     .align_keys(true)
+    ///     .field_separator(" = ");
+    ///
+    /// assert_eq!(
+    ///     pprint_with(&config, &opts),
+    ///     "\
+    /// Config {
+    ///     name            = \"production\",
+    ///     max_connections = 100,
+    ///     timeout         = 30,
+    /// }"
+    /// );
+    /// ```
+    pub fn align_keys(mut self, align: bool) -> Self {
+        self.align_keys = align;
+        self
+    }
+
+    /// Sets whether the entries of `HashMap`s and `HashSet`s are sorted, so
+    /// they're shown the same way every time.
+    ///
+    /// Entries are ordered by how their keys are written on one line, with
+    /// numbers compared by value, and then cut off at
+    /// [`max_items`](Self::max_items). Every entry sorted has to be formatted,
+    /// so when there are more than `max_items`, only the first 10,000 are
+    /// sorted. Defaults to `false`. See also
+    /// [`DebugMap::sorted`](crate::DebugMap::sorted) and
+    /// [`DebugSet::sorted`](crate::DebugSet::sorted).
+    ///
+    /// ```rust
+    /// use debug2::{pprint_with, PrettyOptions};
+    /// use std::collections::HashSet;
+    ///
+    /// let set: HashSet<u32> = (1..=12).collect();
+    ///
+    /// assert_eq!(
+    ///     pprint_with(&set, &PrettyOptions::new().sort_unordered(true)),
+    ///     "{1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12}"
+    /// );
+    /// ```
+    pub fn sort_unordered(mut self, sort: bool) -> Self {
+        self.sort_unordered = sort;
+        self
+    }
+
+    /// Sets whether the last entry gets a trailing comma when a value is
+    /// split over multiple lines.
+    ///
+    /// Defaults to `true`. Values on one line never have a trailing comma,
+    /// except for 1-tuples, which need it.
+    ///
+    /// ```rust
+    /// use debug2::{pprint_with, PrettyOptions};
+    ///
+    /// let opts = PrettyOptions::new().width(10).trailing_comma(false);
+    ///
+    /// assert_eq!(pprint_with(vec![100, 200, 300], &opts), "[\n    100,\n    200,\n    300\n]");
+    /// ```
+    pub fn trailing_comma(mut self, trailing: bool) -> Self {
+        self.trailing_comma = trailing;
+        self
+    }
+
+    /// Sets whether structs written on one line have a space inside their
+    /// braces, as in `Foo { a: 1 }`, or not, as in `Foo {a: 1}`.
+    ///
+    /// Defaults to `true`.
+    ///
+    /// ```rust
+    /// use debug2::{pprint_with, Debug, PrettyOptions};
+    ///
+    /// #[derive(Debug)]
+    /// struct Point {
+    ///     x: i32,
+    ///     y: i32,
+    /// }
+    ///
+    /// let opts = PrettyOptions::new().space_inside_braces(false);
+    ///
+    /// assert_eq!(pprint_with(Point { x: 1, y: 2 }, &opts), "Point {x: 1, y: 2}");
+    /// ```
+    pub fn space_inside_braces(mut self, space: bool) -> Self {
+        self.space_inside_braces = space;
+        self
+    }
+
+    /// Sets the text written between a struct field's name and its value, and
+    /// between a map key and its value.
+    ///
+    /// Defaults to `": "`.
+    ///
+    /// ```rust
+    /// use debug2::{pprint_with, Debug, PrettyOptions};
+    /// use std::collections::BTreeMap;
+    ///
+    /// #[derive(Debug)]
+    /// struct Point {
+    ///     x: i32,
+    ///     y: i32,
+    /// }
+    ///
+    /// let opts = PrettyOptions::new().field_separator(" = ");
+    ///
+    /// assert_eq!(pprint_with(Point { x: 1, y: 2 }, &opts), "Point { x = 1, y = 2 }");
+    ///
+    /// let map: BTreeMap<_, _> = vec![("a", 1)].into_iter().collect();
+    /// assert_eq!(pprint_with(map, &opts), "{\"a\" = 1}");
+    /// ```
+    pub fn field_separator(mut self, separator: impl Into<String>) -> Self {
+        self.field_separator = separator.into();
+        self
+    }
+
+    /// Sets whether the output is colored with ANSI escape sequences, using
+    /// the default [`Theme`].
+    ///
+    /// Escape sequences aren't counted when working out what fits on a
+    /// line, so the layout is the same either way. Defaults to `false`.
+    ///
+    /// ```rust
+    /// use debug2::{pprint_with, PrettyOptions};
+    ///
+    /// assert_eq!(
+    ///     pprint_with(Some("hi"), &PrettyOptions::new().color(true)),
+    ///     "\x1b[1mSome\x1b[0m(\x1b[32m\"hi\"\x1b[0m)"
+    /// );
+    /// ```
+    pub fn color(mut self, color: bool) -> Self {
+        self.theme = if color { Some(Theme::new()) } else { None };
+        self
+    }
+
+    /// Colors the output with the given [`Theme`].
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    /// Colors `text` as `kind`, if there's a theme.
+    pub(crate) fn paint<'t>(&self, kind: TokenKind, text: &'t str) -> Cow<'t, str> {
+        match &self.theme {
+            Some(theme) => theme.paint(kind, text),
+            None => Cow::Borrowed(text),
+        }
+    }
+
+    pub(crate) fn indent_width(&self) -> usize {
+        crate::text_width(&self.indent)
+    }
+}
+
+/// How the entries of a list of structs are laid out, see
+/// [`PrettyOptions::align`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Align {
+    /// Each struct is written as usual.
+    Off,
+    /// Values are padded so each field starts in the same column.
+    Fields,
+    /// The field names are written once, as the header of a table with a row
+    /// for each struct. The structs' names aren't shown, so this is only
+    /// done when they all have the same name.
+    Table,
+}
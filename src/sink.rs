@@ -0,0 +1,125 @@
+use std::fmt::Result;
+
+use crate::TokenKind;
+
+/// Receives the structure of a value, instead of the text it's printed as.
+///
+/// Passing a `Sink` to [`visit`](crate::visit) calls its methods as the
+/// value's [`Debug`](crate::Debug) implementation uses the builders on
+/// [`Formatter`](crate::Formatter), so a renderer for another format can be
+/// written once and used with every type that implements `Debug`.
+///
+/// A value is either:
+/// - A call to one of the `begin_*` methods, then its contents, then a call
+///   to [`end`](Self::end). Each field of a struct is a call to
+///   [`field`](Self::field) followed by its value. Each entry of a map is its
+///   key followed by its value.
+/// - A call to [`scalar`](Self::scalar), like the string `"\"hi\""`, or the
+///   number `"1"`.
+///
+/// Options like [`PrettyOptions::max_items`](crate::PrettyOptions::max_items)
+/// still apply, with anything left out reported by [`more`](Self::more) and
+/// [`non_exhaustive`](Self::non_exhaustive).
+///
+/// # Examples
+///
+/// ```rust
+/// use debug2::{visit, Sink, TokenKind};
+/// use std::fmt;
+///
+/// /// Writes values as S-expressions.
+/// #[derive(Default)]
+/// struct Lisp(String);
+///
+/// impl Lisp {
+///     fn open(&mut self, head: &str) -> fmt::Result {
+///         self.0.push('(');
+///         self.0.push_str(head);
+///         Ok(())
+///     }
+/// }
+///
+/// impl Sink for Lisp {
+///     fn begin_struct(&mut self, name: &str) -> fmt::Result {
+///         self.open(name)
+///     }
+///     fn begin_tuple(&mut self, name: &str) -> fmt::Result {
+///         self.open(name)
+///     }
+///     fn begin_list(&mut self) -> fmt::Result {
+///         self.open("list")
+///     }
+///     fn begin_set(&mut self) -> fmt::Result {
+///         self.open("set")
+///     }
+///     fn begin_map(&mut self) -> fmt::Result {
+///         self.open("map")
+///     }
+///     fn field(&mut self, name: &str) -> fmt::Result {
+///         self.0.push_str(" :");
+///         self.0.push_str(name);
+///         Ok(())
+///     }
+///     fn scalar(&mut self, _kind: TokenKind, text: &str) -> fmt::Result {
+///         self.0.push(' ');
+///         self.0.push_str(text);
+///         Ok(())
+///     }
+///     fn end(&mut self) -> fmt::Result {
+///         self.0.push(')');
+///         Ok(())
+///     }
+/// }
+///
+/// let mut lisp = Lisp::default();
+/// visit((vec![1, 2], Some("three")), &mut lisp).unwrap();
+/// assert_eq!(lisp.0, "((list 1 2)(Some \"three\"))");
+/// ```
+pub trait Sink {
+    /// Starts a struct, or an enum variant with named fields.
+    fn begin_struct(&mut self, name: &str) -> Result;
+
+    /// Starts a tuple struct, or an enum variant with unnamed fields. Tuples
+    /// have an empty name.
+    fn begin_tuple(&mut self, name: &str) -> Result;
+
+    /// Starts a list.
+    fn begin_list(&mut self) -> Result;
+
+    /// Starts a set.
+    fn begin_set(&mut self) -> Result;
+
+    /// Starts a map.
+    fn begin_map(&mut self) -> Result;
+
+    /// Starts a field of a struct, which is followed by its value.
+    fn field(&mut self, name: &str) -> Result;
+
+    /// A value that isn't made with the builders, like a number or a string.
+    ///
+    /// If it's written in several parts, `kind` is the kind of the first.
+    fn scalar(&mut self, kind: TokenKind, text: &str) -> Result;
+
+    /// Notes that some of the contents of the current value aren't shown,
+    /// either because of [`finish_non_exhaustive`] or
+    /// [`PrettyOptions::max_depth`](crate::PrettyOptions::max_depth).
+    ///
+    /// [`finish_non_exhaustive`]: crate::DebugStruct::finish_non_exhaustive
+    fn non_exhaustive(&mut self) -> Result {
+        Ok(())
+    }
+
+    /// Notes that `count` more entries of the current list, set or map aren't
+    /// shown, because of a limit.
+    ///
+    /// If the entries come from an iterator that doesn't know how many it has
+    /// left, at most 10,000 are counted, and `at_least` is `true` if there
+    /// were more than that, so there may be more than `count`.
+    fn more(&mut self, count: usize, at_least: bool) -> Result {
+        let _ = (count, at_least);
+        Ok(())
+    }
+
+    /// Ends the value started by the last unmatched `begin_*` call.
+    fn end(&mut self) -> Result;
+}
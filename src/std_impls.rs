@@ -1,9 +1,21 @@
+use std::any::Any;
+use std::backtrace::{Backtrace, BacktraceStatus};
 use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque};
+use std::error::Error;
+use std::net::{
+    IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6, TcpListener, TcpStream,
+    UdpSocket,
+};
 use std::ops::Deref;
 use std::rc::Rc;
 use std::sync::Arc;
+use std::thread::{Thread, ThreadId};
+use std::time::{Instant, SystemTime};
 
-use crate::{Debug, Formatter, Result};
+use crate::bytes::fmt_bytes;
+use crate::numbers::{fmt_float, fmt_int};
+use crate::strings::fmt_str;
+use crate::{BytesStyle, Debug, ErrorReport, Formatter, Result, TokenKind};
 
 macro_rules! std_debug {
     ($($t:ty),+) => {
@@ -17,11 +29,72 @@ macro_rules! std_debug {
     };
 }
 
-std_debug! {
-    String, &str, bool, (),
+impl Debug for bool {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_token(TokenKind::Keyword, if *self { "true" } else { "false" })
+    }
+}
+
+macro_rules! int_debug {
+    ($($t:ty),+) => {
+        $(
+            impl Debug for $t {
+                fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+                    fmt_int(*self, f)
+                }
+            }
+        )+
+    };
+}
+
+int_debug! {
     i8, i16, i32, i64, i128, isize,
-    u8, u16, u32, u64, u128, usize,
-    f32, f64
+    u16, u32, u64, u128, usize
+}
+
+impl Debug for f32 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        fmt_float(*self, f)
+    }
+}
+
+impl Debug for f64 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        fmt_float(*self, f)
+    }
+}
+
+// These all have a `std::fmt::Debug` impl that prints them without quotes, so
+// forward to that instead of going through `debug_struct`.
+std_debug! {
+    IpAddr, Ipv4Addr, Ipv6Addr,
+    SocketAddr, SocketAddrV4, SocketAddrV6,
+    SystemTime, Instant, ThreadId
+}
+
+impl Debug for str {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        fmt_str(self, f)
+    }
+}
+
+impl Debug for String {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        fmt_str(self, f)
+    }
+}
+
+impl Debug for u8 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        fmt_int(*self, f)
+    }
+
+    fn fmt_slice(slice: &[Self], f: &mut Formatter<'_>) -> Result {
+        match f.opts.bytes {
+            BytesStyle::List => f.debug_list().entries(slice.iter()).finish(),
+            style => fmt_bytes(slice, style, f),
+        }
+    }
 }
 
 impl<T: ?Sized + Debug> Debug for &T {
@@ -72,6 +145,86 @@ impl<T: ?Sized> Debug for *mut T {
     }
 }
 
+// Function pointers
+//
+// These cover safe and unsafe pointers with up to 12 arguments, for the Rust,
+// "C" and "system" ABIs, plus variadic "C" pointers. Pointers with
+// higher-ranked lifetimes, like `fn(&T)` (really `for<'a> fn(&'a T)`), aren't
+// instances of `fn(A)`, so need impls of their own. Those can only be written
+// for a borrowed first argument, and rustc warns they may overlap with the
+// impls for `fn(A)` in future versions, so they're behind the default
+// `higher-ranked-fn-ptrs` feature.
+
+macro_rules! fn_ptr {
+    ($($arg:ident),*) => {
+        fn_ptr! { @impl [fn] $($arg),* }
+        fn_ptr! { @impl [unsafe fn] $($arg),* }
+        fn_ptr! { @impl [extern "C" fn] $($arg),* }
+        fn_ptr! { @impl [unsafe extern "C" fn] $($arg),* }
+        fn_ptr! { @impl [extern "system" fn] $($arg),* }
+        fn_ptr! { @impl [unsafe extern "system" fn] $($arg),* }
+    };
+    (@impl [$($kw:tt)*] $($arg:ident),*) => {
+        #[cfg_attr(feature = "higher-ranked-fn-ptrs", allow(coherence_leak_check))]
+        impl<Ret, $($arg),*> Debug for $($kw)* ($($arg),*) -> Ret {
+            fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+                f.write_debug(&(*self as *const ()))
+            }
+        }
+
+        #[cfg(feature = "higher-ranked-fn-ptrs")]
+        #[allow(coherence_leak_check)]
+        impl<Ret, Arg: ?Sized, $($arg),*> Debug for for<'a> $($kw)* (&'a Arg, $($arg),*) -> Ret {
+            fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+                f.write_debug(&(*self as *const ()))
+            }
+        }
+    };
+}
+
+macro_rules! variadic_fn_ptr {
+    ($($arg:ident),+) => {
+        impl<Ret, $($arg),+> Debug for extern "C" fn($($arg),+, ...) -> Ret {
+            fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+                f.write_debug(&(*self as *const ()))
+            }
+        }
+
+        impl<Ret, $($arg),+> Debug for unsafe extern "C" fn($($arg),+, ...) -> Ret {
+            fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+                f.write_debug(&(*self as *const ()))
+            }
+        }
+    };
+}
+
+fn_ptr! {}
+fn_ptr! { A }
+fn_ptr! { A, B }
+fn_ptr! { A, B, C }
+fn_ptr! { A, B, C, D }
+fn_ptr! { A, B, C, D, E }
+fn_ptr! { A, B, C, D, E, F }
+fn_ptr! { A, B, C, D, E, F, G }
+fn_ptr! { A, B, C, D, E, F, G, H }
+fn_ptr! { A, B, C, D, E, F, G, H, I }
+fn_ptr! { A, B, C, D, E, F, G, H, I, J }
+fn_ptr! { A, B, C, D, E, F, G, H, I, J, K }
+fn_ptr! { A, B, C, D, E, F, G, H, I, J, K, L }
+
+variadic_fn_ptr! { A }
+variadic_fn_ptr! { A, B }
+variadic_fn_ptr! { A, B, C }
+variadic_fn_ptr! { A, B, C, D }
+variadic_fn_ptr! { A, B, C, D, E }
+variadic_fn_ptr! { A, B, C, D, E, F }
+variadic_fn_ptr! { A, B, C, D, E, F, G }
+variadic_fn_ptr! { A, B, C, D, E, F, G, H }
+variadic_fn_ptr! { A, B, C, D, E, F, G, H, I }
+variadic_fn_ptr! { A, B, C, D, E, F, G, H, I, J }
+variadic_fn_ptr! { A, B, C, D, E, F, G, H, I, J, K }
+variadic_fn_ptr! { A, B, C, D, E, F, G, H, I, J, K, L }
+
 macro_rules! list_like {
     ($($t:ty),+) => {
         $(
@@ -85,7 +238,19 @@ macro_rules! list_like {
 }
 
 list_like! {
-    [T], Vec<T>, VecDeque<T>, LinkedList<T>, BinaryHeap<T>
+    VecDeque<T>, LinkedList<T>, BinaryHeap<T>
+}
+
+impl<T: Debug> Debug for [T] {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        T::fmt_slice(self, f)
+    }
+}
+
+impl<T: Debug> Debug for Vec<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        T::fmt_slice(self, f)
+    }
 }
 
 impl<K, V, S> Debug for HashMap<K, V, S>
@@ -94,7 +259,12 @@ where
     V: Debug,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        f.debug_map().entries(self.iter()).finish()
+        let sort = f.opts.sort_unordered;
+        let mut map = f.debug_map();
+        if sort {
+            map.sorted();
+        }
+        map.entries(self.iter()).finish()
     }
 }
 impl<K: Debug, V: Debug> Debug for BTreeMap<K, V> {
@@ -107,7 +277,12 @@ where
     T: Debug,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        f.debug_set().entries(self.iter()).finish()
+        let sort = f.opts.sort_unordered;
+        let mut set = f.debug_set();
+        if sort {
+            set.sorted();
+        }
+        set.entries(self.iter()).finish()
     }
 }
 impl<T> Debug for BTreeSet<T>
@@ -122,7 +297,7 @@ impl<T: Debug> Debug for Option<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         match self {
             Some(v) => f.debug_tuple("Some").field(v).finish(),
-            None => f.debug_tuple("None").finish(),
+            None => f.write_token(TokenKind::Keyword, "None"),
         }
     }
 }
@@ -134,7 +309,17 @@ macro_rules! peel {
 }
 
 macro_rules! tuple {
-    () => ();
+    () => (
+        impl Debug for () {
+            fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+                match f.sink() {
+                    // Sinks see the unit type as the empty tuple it is.
+                    Some(_) => f.debug_tuple("").finish(),
+                    None => f.write_punct("()"),
+                }
+            }
+        }
+    );
     ( $($name:ident,)+ ) => (
         impl<$($name:Debug),+> Debug for ($($name,)+) where last_type!($($name,)+): ?Sized {
             #[allow(non_snake_case, unused_assignments)]
@@ -157,7 +342,7 @@ macro_rules! last_type {
     ($a:ident, $($rest_a:ident,)+) => { last_type!($($rest_a,)+) };
 }
 
-tuple! { T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, }
+tuple! { T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11, T12, T13, T14, T15, }
 
 impl<T: ?Sized> Debug for std::marker::PhantomData<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
@@ -202,7 +387,7 @@ impl<T: ?Sized + Debug> Debug for std::cell::Ref<'_, T> {
 
 impl<T: ?Sized + Debug> Debug for std::cell::RefMut<'_, T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        Debug::fmt(&*(self.deref()), f)
+        Debug::fmt(self.deref(), f)
     }
 }
 
@@ -211,3 +396,163 @@ impl<T: ?Sized> Debug for std::cell::UnsafeCell<T> {
         f.debug_struct("UnsafeCell").finish_non_exhaustive()
     }
 }
+
+impl Debug for Thread {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.debug_struct("Thread")
+            .field("id", &self.id())
+            .field("name", &self.name())
+            .finish_non_exhaustive()
+    }
+}
+
+// Sockets
+
+impl Debug for TcpStream {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let mut res = f.debug_struct("TcpStream");
+
+        if let Ok(addr) = self.local_addr() {
+            res.field("addr", &addr);
+        }
+
+        if let Ok(peer) = self.peer_addr() {
+            res.field("peer", &peer);
+        }
+
+        res.finish()
+    }
+}
+
+impl Debug for TcpListener {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let mut res = f.debug_struct("TcpListener");
+
+        if let Ok(addr) = self.local_addr() {
+            res.field("addr", &addr);
+        }
+
+        res.finish()
+    }
+}
+
+impl Debug for UdpSocket {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let mut res = f.debug_struct("UdpSocket");
+
+        if let Ok(addr) = self.local_addr() {
+            res.field("addr", &addr);
+        }
+
+        if let Ok(peer) = self.peer_addr() {
+            res.field("peer", &peer);
+        }
+
+        res.finish()
+    }
+}
+
+// Trait objects
+
+impl Debug for dyn Any {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.debug_struct("Any").finish_non_exhaustive()
+    }
+}
+
+impl Debug for dyn Any + Send {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.debug_struct("Any").finish_non_exhaustive()
+    }
+}
+
+impl Debug for dyn Any + Send + Sync {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.debug_struct("Any").finish_non_exhaustive()
+    }
+}
+
+impl Debug for dyn Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        Debug::fmt(&ErrorReport(self), f)
+    }
+}
+
+impl Debug for dyn Error + Send {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        Debug::fmt(self as &dyn Error, f)
+    }
+}
+
+impl Debug for dyn Error + Send + Sync {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        Debug::fmt(self as &dyn Error, f)
+    }
+}
+
+/// Shows one frame per entry, with its symbol followed by its location if
+/// known.
+impl Debug for Backtrace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self.status() {
+            BacktraceStatus::Unsupported => return f.write_str("<unsupported>"),
+            BacktraceStatus::Disabled => return f.write_str("<disabled>"),
+            _ => {}
+        }
+
+        // `Backtrace::frames` isn't stable, so recover the frames from the
+        // `Display` output. That format isn't stable either, so if it isn't
+        // what's expected, show each of its lines rather than guessing.
+        let text = self.to_string();
+        let frames = backtrace_frames(&text).unwrap_or_else(|| {
+            text.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_owned)
+                .collect()
+        });
+
+        struct Frame<'a>(&'a str);
+
+        impl Debug for Frame<'_> {
+            fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+                f.write_str(self.0)
+            }
+        }
+
+        f.write_str("Backtrace ")?;
+        f.debug_list()
+            .entries(frames.iter().map(|frame| Frame(frame)))
+            .finish()
+    }
+}
+
+/// Splits the `Display` output of a [`Backtrace`] into frames, or returns
+/// `None` if it isn't laid out as expected.
+///
+/// Each frame is a numbered line with its symbol, like `3: main`, optionally
+/// followed by a line with its location, like `at src/main.rs:1:1`.
+fn backtrace_frames(text: &str) -> Option<Vec<String>> {
+    let mut frames: Vec<String> = Vec::new();
+
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let symbol = line
+            .split_once(": ")
+            .filter(|(n, _)| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()));
+
+        match (symbol, frames.last_mut()) {
+            (Some((_, symbol)), _) => frames.push(symbol.to_owned()),
+            (None, Some(frame)) if line.starts_with("at ") => {
+                frame.push(' ');
+                frame.push_str(line);
+            }
+            _ => return None,
+        }
+    }
+
+    if frames.is_empty() {
+        None
+    } else {
+        Some(frames)
+    }
+}
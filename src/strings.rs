@@ -0,0 +1,71 @@
+use crate::{Formatter, Result, TokenKind};
+
+pub(crate) fn fmt_str(s: &str, f: &mut Formatter<'_>) -> Result {
+    let (shown, hidden) = match f.opts.max_string_len {
+        Some(max) if s.len() > max => {
+            let mut end = max;
+            while !s.is_char_boundary(end) {
+                end -= 1;
+            }
+            (&s[..end], s.len() - end)
+        }
+        _ => (s, 0),
+    };
+
+    let escaped = format!("{:?}", shown);
+
+    if f.is_pretty() && f.opts.wrap_strings && escaped.len() > f.remaining_on_line() {
+        // Keep whitespace at the end of the line, as the escaped newline
+        // skips any at the start of the next one.
+        let words = shown.split_inclusive(char::is_whitespace).map(|word| {
+            let escaped = format!("{:?}", word);
+            escaped[1..escaped.len() - 1].to_owned()
+        });
+        write_wrapped(f, "\"", words, " ", "\"")?;
+    } else {
+        f.write_token(TokenKind::String, &escaped)?;
+    }
+
+    if hidden > 0 {
+        f.write_punct(&format!(" ({} more bytes)", crate::group_digits(hidden)))?;
+    }
+    Ok(())
+}
+
+/// Writes a string literal made of `pieces`, moving to a new line (with an
+/// escaped newline) before any piece that wouldn't fit on the current one.
+///
+/// Continuation lines start with `indent`, which doesn't change the value of
+/// the literal, as an escaped newline skips any whitespace that follows it.
+pub(crate) fn write_wrapped<I>(
+    f: &mut Formatter<'_>,
+    open: &str,
+    pieces: I,
+    indent: &str,
+    close: &str,
+) -> Result
+where
+    I: IntoIterator<Item = String>,
+{
+    f.write_token(TokenKind::String, open)?;
+
+    let mut line_empty = true;
+    for piece in pieces {
+        // Leave space for the trailing `\` or closing quote.
+        if !line_empty && piece.len() + 1 > f.remaining_on_line() {
+            f.write_token(TokenKind::String, "\\\n")?;
+            f.write_token(TokenKind::String, indent)?;
+
+            // A space here would be skipped as well, so escape it.
+            if let Some(rest) = piece.strip_prefix(' ') {
+                f.write_token(TokenKind::String, "\\x20")?;
+                f.write_token(TokenKind::String, rest)?;
+                continue;
+            }
+        }
+        f.write_token(TokenKind::String, &piece)?;
+        line_empty = false;
+    }
+
+    f.write_token(TokenKind::String, close)
+}
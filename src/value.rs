@@ -0,0 +1,308 @@
+use std::fmt::{Error, Result};
+
+use crate::{Debug, Formatter, PrettyOptions, Sink, TokenKind};
+
+/// An owned copy of how a value is formatted, made by [`to_value`].
+///
+/// A `Value` can be kept after the value it was made from has changed or gone,
+/// and formats the same way the original did.
+///
+/// # Examples
+///
+/// ```rust
+/// use debug2::{pprint, to_value, Debug, TokenKind, Value};
+///
+/// #[derive(Debug)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// let value = to_value(Point { x: 1, y: 2 });
+///
+/// assert_eq!(
+///     value,
+///     Value::Struct {
+///         name: "Point".to_owned(),
+///         fields: vec![
+///             ("x".to_owned(), Value::Atom(TokenKind::Number, "1".to_owned())),
+///             ("y".to_owned(), Value::Atom(TokenKind::Number, "2".to_owned())),
+///         ],
+///     }
+/// );
+/// assert_eq!(pprint(&value), "Point { x: 1, y: 2 }");
+/// ```
+#[derive(Clone, PartialEq, Eq, std::fmt::Debug)]
+pub enum Value {
+    /// A struct, or an enum variant with named fields.
+    Struct {
+        /// The name of the struct or variant.
+        name: String,
+        /// The names and values of the fields.
+        fields: Vec<(String, Value)>,
+    },
+    /// A tuple struct, or an enum variant with unnamed fields. Tuples have an
+    /// empty name, and one with no fields is shown as `()`.
+    Tuple {
+        /// The name of the struct or variant.
+        name: String,
+        /// The values of the fields.
+        fields: Vec<Value>,
+    },
+    /// A list of entries.
+    List(Vec<Value>),
+    /// A set of entries.
+    Set(Vec<Value>),
+    /// Keys and their values.
+    Map(Vec<(Value, Value)>),
+    /// Anything not made with the builders, as it's written, like `1` or
+    /// `"hi"`, and what kind of token it is, which sets its color.
+    Atom(TokenKind, String),
+    /// A struct, tuple, list, set or map with contents that aren't shown.
+    NonExhaustive {
+        /// What is shown.
+        value: Box<Value>,
+        /// How many more entries there are, if the entries were cut off by
+        /// a limit rather than being hidden with `..`.
+        more: Option<usize>,
+        /// Whether there were too many entries to count, so there may be more
+        /// than `more`.
+        at_least: bool,
+    },
+}
+
+/// Records how an item is formatted, so it can be formatted again later.
+///
+/// See [`Value`] for an example.
+///
+/// # Panics
+///
+/// This will panic if `<T as Debug>::fmt` returns an error
+pub fn to_value<T: Debug>(x: T) -> Value {
+    to_value_with(x, &PrettyOptions::default())
+}
+
+/// Like [`to_value`], but with the scalars written using `opts`.
+pub(crate) fn to_value_with<T: Debug>(x: T, opts: &PrettyOptions) -> Value {
+    let mut recorder = Recorder::default();
+    crate::visit_with(x, opts, &mut recorder).unwrap();
+    recorder.finish().unwrap()
+}
+
+/// Builds a [`Value`] from the calls to a [`Sink`].
+#[derive(Default)]
+struct Recorder {
+    // The values that have been started but not ended, outermost first.
+    stack: Vec<Partial>,
+    // The value once it's done.
+    done: Option<Value>,
+}
+
+/// A value that hasn't ended yet.
+struct Partial {
+    value: Value,
+    // The name of the next field of a struct.
+    field: Option<String>,
+    // The key of the next entry of a map.
+    key: Option<Value>,
+    non_exhaustive: bool,
+    more: Option<usize>,
+    at_least: bool,
+}
+
+impl Recorder {
+    fn begin(&mut self, value: Value) -> Result {
+        self.stack.push(Partial {
+            value,
+            field: None,
+            key: None,
+            non_exhaustive: false,
+            more: None,
+            at_least: false,
+        });
+        Ok(())
+    }
+
+    /// Adds a finished value to the one it's inside of.
+    fn push(&mut self, value: Value) -> Result {
+        let parent = match self.stack.last_mut() {
+            Some(parent) => parent,
+            None if self.done.is_none() => {
+                self.done = Some(value);
+                return Ok(());
+            }
+            None => return Err(Error),
+        };
+        match &mut parent.value {
+            Value::Struct { fields, .. } => fields.push((parent.field.take().ok_or(Error)?, value)),
+            Value::Tuple { fields, .. } | Value::List(fields) | Value::Set(fields) => {
+                fields.push(value)
+            }
+            Value::Map(entries) => match parent.key.take() {
+                Some(key) => entries.push((key, value)),
+                None => parent.key = Some(value),
+            },
+            Value::Atom(..) | Value::NonExhaustive { .. } => unreachable!(),
+        }
+        Ok(())
+    }
+
+    fn top(&mut self) -> std::result::Result<&mut Partial, Error> {
+        self.stack.last_mut().ok_or(Error)
+    }
+
+    fn finish(self) -> std::result::Result<Value, Error> {
+        if !self.stack.is_empty() {
+            return Err(Error);
+        }
+        self.done.ok_or(Error)
+    }
+}
+
+impl Sink for Recorder {
+    fn begin_struct(&mut self, name: &str) -> Result {
+        self.begin(Value::Struct {
+            name: name.to_owned(),
+            fields: Vec::new(),
+        })
+    }
+
+    fn begin_tuple(&mut self, name: &str) -> Result {
+        self.begin(Value::Tuple {
+            name: name.to_owned(),
+            fields: Vec::new(),
+        })
+    }
+
+    fn begin_list(&mut self) -> Result {
+        self.begin(Value::List(Vec::new()))
+    }
+
+    fn begin_set(&mut self) -> Result {
+        self.begin(Value::Set(Vec::new()))
+    }
+
+    fn begin_map(&mut self) -> Result {
+        self.begin(Value::Map(Vec::new()))
+    }
+
+    fn field(&mut self, name: &str) -> Result {
+        self.top()?.field = Some(name.to_owned());
+        Ok(())
+    }
+
+    fn scalar(&mut self, kind: TokenKind, text: &str) -> Result {
+        self.push(Value::Atom(kind, text.to_owned()))
+    }
+
+    fn non_exhaustive(&mut self) -> Result {
+        self.top()?.non_exhaustive = true;
+        Ok(())
+    }
+
+    fn more(&mut self, count: usize, at_least: bool) -> Result {
+        let top = self.top()?;
+        top.more = Some(count);
+        top.at_least = at_least;
+        Ok(())
+    }
+
+    fn end(&mut self) -> Result {
+        let partial = self.stack.pop().ok_or(Error)?;
+        let value = if partial.non_exhaustive || partial.more.is_some() {
+            Value::NonExhaustive {
+                value: Box::new(partial.value),
+                more: partial.more,
+                at_least: partial.at_least,
+            }
+        } else {
+            partial.value
+        };
+        self.push(value)
+    }
+}
+
+impl Debug for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Value::NonExhaustive {
+                value,
+                more,
+                at_least,
+            } => fmt_value(value, Some(more.map(|more| (more, *at_least))), f),
+            value => fmt_value(value, None, f),
+        }
+    }
+}
+
+/// Formats a value, with `rest` set if some of its contents aren't shown, to
+/// how many more entries there are and whether there may be more than that.
+fn fmt_value(value: &Value, rest: Option<Option<(usize, bool)>>, f: &mut Formatter<'_>) -> Result {
+    match value {
+        Value::Struct { name, fields } => {
+            let mut s = f.debug_struct(name);
+            for (name, value) in fields {
+                s.field(name, value);
+            }
+            match rest {
+                Some(_) => s.finish_non_exhaustive(),
+                None => s.finish(),
+            }
+        }
+        // The unit type, which `debug_tuple` wouldn't write anything for.
+        Value::Tuple { name, fields } if name.is_empty() && fields.is_empty() && rest.is_none() => {
+            ().fmt(f)
+        }
+        Value::Tuple { name, fields } => {
+            let mut t = f.debug_tuple(name);
+            for value in fields {
+                t.field(value);
+            }
+            if rest.is_some() {
+                t.field(&Rest);
+            }
+            t.finish()
+        }
+        Value::List(entries) => {
+            let mut list = f.debug_list();
+            list.entries(entries);
+            match rest {
+                Some(Some((more, at_least))) => list.hidden(more, at_least),
+                Some(None) => list.elided(),
+                None => &mut list,
+            }
+            .finish()
+        }
+        Value::Set(entries) => {
+            let mut set = f.debug_set();
+            set.entries(entries);
+            match rest {
+                Some(Some((more, at_least))) => set.hidden(more, at_least),
+                Some(None) => set.elided(),
+                None => &mut set,
+            }
+            .finish()
+        }
+        Value::Map(entries) => {
+            let mut map = f.debug_map();
+            map.entries(entries.iter().map(|(k, v)| (k, v)));
+            match rest {
+                Some(Some((more, at_least))) => map.hidden(more, at_least),
+                Some(None) => map.elided(),
+                None => &mut map,
+            }
+            .finish()
+        }
+        Value::Atom(kind, text) => f.write_token(*kind, text),
+        Value::NonExhaustive { .. } => value.fmt(f),
+    }
+}
+
+/// The `..` standing in for contents that aren't shown.
+struct Rest;
+
+impl Debug for Rest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_punct("..")
+    }
+}
@@ -0,0 +1,163 @@
+use debug2::{pprint_with, Align, Debug, Formatter, PrettyOptions};
+use insta::assert_snapshot;
+use std::fmt;
+
+macro_rules! check {
+    ($e:expr, $align:expr) => {
+        assert_snapshot!(pprint_with($e, &PrettyOptions::new().align($align)))
+    };
+}
+
+#[derive(Debug)]
+struct Point {
+    x: i32,
+    y: i32,
+    z: i32,
+}
+
+#[derive(Debug)]
+struct Other {
+    x: i32,
+    y: i32,
+    z: i32,
+}
+
+#[derive(Debug)]
+struct Person {
+    name: &'static str,
+    tags: Vec<&'static str>,
+}
+
+fn points() -> Vec<Point> {
+    (0..6)
+        .map(|i| Point {
+            x: i * i * i,
+            y: 1000 / (i + 1),
+            z: -i,
+        })
+        .collect()
+}
+
+#[test]
+fn fields() {
+    check!(points(), Align::Fields);
+}
+
+#[test]
+fn table() {
+    check!(points(), Align::Table);
+}
+
+#[test]
+fn nested_values() {
+    let people = vec![
+        Person {
+            name: "Alice",
+            tags: vec!["admin", "ops"],
+        },
+        Person {
+            name: "Bob",
+            tags: vec![],
+        },
+        Person {
+            name: "Christopher",
+            tags: vec!["dev"],
+        },
+    ];
+    check!(&people, Align::Fields);
+    check!(&people, Align::Table);
+}
+
+fn mixed_names() -> Vec<Box<dyn Debug>> {
+    vec![
+        Box::new(Point { x: 1, y: 2, z: 3 }),
+        Box::new(Other {
+            x: 100,
+            y: 200,
+            z: 300,
+        }),
+        Box::new(Point {
+            x: 10,
+            y: 20,
+            z: 30,
+        }),
+        Box::new(Other { x: 4, y: 5, z: 6 }),
+    ]
+}
+
+#[test]
+fn different_names() {
+    let entries = mixed_names();
+    check!(entries, Align::Fields);
+}
+
+#[test]
+fn different_names_table() {
+    // A table doesn't show names, so structs with different names aren't put
+    // in one.
+    let entries = mixed_names();
+    check!(entries, Align::Table);
+}
+
+#[test]
+fn different_fields() {
+    // Not every entry has the same fields, so nothing is aligned.
+    let entries: Vec<Box<dyn Debug>> = vec![
+        Box::new(Point { x: 1, y: 2, z: 3 }),
+        Box::new(Person {
+            name: "Alice",
+            tags: vec!["admin", "ops"],
+        }),
+        Box::new(Point {
+            x: 10,
+            y: 20,
+            z: 30,
+        }),
+    ];
+    check!(entries, Align::Fields);
+}
+
+struct Hidden(i32);
+
+impl Debug for Hidden {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Hidden")
+            .field("shown", &self.0)
+            .finish_non_exhaustive()
+    }
+}
+
+#[test]
+fn non_exhaustive() {
+    let entries: Vec<Hidden> = (0..8).map(|i| Hidden(i * 1000)).collect();
+    check!(entries, Align::Fields);
+}
+
+#[test]
+fn too_wide() {
+    // Each entry fits on a line, but not once they're padded.
+    let entries = vec![
+        Person {
+            name: "A fairly long name, over half a line",
+            tags: vec!["a"],
+        },
+        Person {
+            name: "A",
+            tags: vec!["a long tag", "and another"],
+        },
+    ];
+    check!(entries, Align::Fields);
+}
+
+#[test]
+fn with_limit() {
+    assert_snapshot!(pprint_with(
+        points(),
+        &PrettyOptions::new().align(Align::Table).max_items(3)
+    ));
+}
+
+#[test]
+fn off_by_default() {
+    assert_snapshot!(pprint_with(points(), &PrettyOptions::new()));
+}
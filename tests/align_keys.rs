@@ -0,0 +1,88 @@
+use debug2::{pprint_with, Debug, PrettyOptions};
+use insta::assert_snapshot;
+use std::collections::BTreeMap;
+
+macro_rules! check {
+    ($e:expr) => {
+        check!($e, PrettyOptions::new())
+    };
+    ($e:expr, $opts:expr) => {
+        assert_snapshot!(pprint_with($e, &$opts.align_keys(true)))
+    };
+}
+
+#[derive(Debug)]
+struct Config {
+    name: &'static str,
+    max_connections: u32,
+    servers: Vec<Server>,
+    env: BTreeMap<&'static str, &'static str>,
+}
+
+#[derive(Debug)]
+struct Server {
+    host: &'static str,
+    port: u16,
+    healthy: bool,
+    tags: Vec<&'static str>,
+}
+
+fn config() -> Config {
+    Config {
+        name: "production",
+        max_connections: 100,
+        servers: vec![
+            Server {
+                host: "alpha.example.com",
+                port: 443,
+                healthy: true,
+                tags: vec!["primary", "eu-west", "ssd", "monitored", "v2"],
+            },
+            Server {
+                host: "b.example.com",
+                port: 80,
+                healthy: false,
+                tags: vec![],
+            },
+        ],
+        env: vec![
+            ("HOME", "/root"),
+            ("RUST_BACKTRACE", "1"),
+            ("PATH", "/usr/local/bin:/usr/bin:/bin:/usr/sbin:/sbin"),
+        ]
+        .into_iter()
+        .collect(),
+    }
+}
+
+#[test]
+fn nested_blocks() {
+    check!(config());
+}
+
+#[test]
+fn equals_separator() {
+    check!(config(), PrettyOptions::new().field_separator(" = "));
+}
+
+#[test]
+fn long_keys() {
+    // Each map is padded on its own.
+    let mut maps = BTreeMap::new();
+    maps.insert(
+        "short",
+        vec![("a", 1), ("bb", 2), ("ccc", 3)]
+            .into_iter()
+            .collect::<BTreeMap<_, _>>(),
+    );
+    maps.insert(
+        "a much longer key",
+        vec![("dddddddd", 4), ("e", 5)].into_iter().collect(),
+    );
+    check!(maps, PrettyOptions::new().width(30));
+}
+
+#[test]
+fn off_by_default() {
+    assert_snapshot!(pprint_with(config(), &PrettyOptions::new()));
+}
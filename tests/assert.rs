@@ -0,0 +1,104 @@
+use debug2::{assert_eq, assert_ne, with_options, Debug, IntStyle, PrettyOptions};
+use insta::assert_snapshot;
+use std::panic::{self, UnwindSafe};
+
+#[derive(Debug, PartialEq)]
+struct Order {
+    id: u32,
+    customer: &'static str,
+    items: Vec<Item>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Item {
+    sku: &'static str,
+    quantity: u32,
+}
+
+fn order(quantity: u32) -> Order {
+    Order {
+        id: 1042,
+        customer: "Ada Lovelace <ada@example.com>",
+        items: vec![
+            Item {
+                sku: "notebook-a5-dotted",
+                quantity: 2,
+            },
+            Item {
+                sku: "fountain-pen-fine",
+                quantity,
+            },
+        ],
+    }
+}
+
+fn panic_message(f: impl FnOnce() + UnwindSafe) -> String {
+    let payload = panic::catch_unwind(f).unwrap_err();
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload.downcast_ref::<&str>().unwrap().to_string(),
+    }
+}
+
+#[test]
+fn passes() {
+    assert_eq!(order(1), order(1));
+    assert_eq!(order(1), order(1), "with a message");
+    assert_ne!(order(1), order(2));
+    assert_ne!(order(1), order(2), "with a message {}", 1);
+    assert_eq!("a", "a".to_owned());
+}
+
+#[test]
+fn eq_fails() {
+    assert_snapshot!(panic_message(|| assert_eq!(order(1), order(3))));
+}
+
+#[test]
+fn eq_fails_short() {
+    assert_snapshot!(panic_message(|| assert_eq!(
+        vec![1, 2, 3],
+        vec![1, 2, 4],
+        "checking {}",
+        "lists"
+    )));
+}
+
+#[test]
+fn ne_fails() {
+    assert_snapshot!(panic_message(|| assert_ne!(Some(1), Some(1))));
+}
+
+#[test]
+fn eq_fails_same_text() {
+    assert_snapshot!(panic_message(|| assert_eq!(
+        vec![1.0, f64::NAN],
+        vec![1.0, f64::NAN]
+    )));
+}
+
+#[test]
+fn eq_fails_with_options() {
+    // The diff is written with the same options as the two sides.
+    let opts = PrettyOptions::new().int_style(IntStyle::Hex);
+    assert_snapshot!(panic_message(|| with_options(opts, || assert_eq!(
+        (255, "x"),
+        (254, "x")
+    ))));
+}
+
+mod shadowed {
+    // A `format_args!` defined by the caller isn't used by the macros.
+    #[allow(unused_macros)]
+    macro_rules! format_args {
+        ($($arg:tt)*) => {
+            compile_error!("the caller's `format_args!` was used")
+        };
+    }
+
+    #[test]
+    fn own_format_args() {
+        debug2::assert_eq!(1, 1, "{}", "message");
+        debug2::assert_ne!(1, 2, "{}", "message");
+    }
+}
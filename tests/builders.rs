@@ -205,6 +205,7 @@ mod debug_tuple {
         }
 
         check!(Foo);
+        check!(Bar);
     }
 }
 
@@ -523,5 +524,96 @@ mod debug_list {
         }
 
         check!(Foo);
+        check!(Bar);
+    }
+}
+
+mod strings {
+    use debug2::{pprint_with, PrettyOptions};
+
+    use super::*;
+
+    const QUERY: &str = "SELECT users.id, users.name, orders.total FROM users \
+        INNER JOIN orders ON orders.user_id = users.id WHERE orders.total > 100 \
+        ORDER BY orders.total DESC LIMIT 10";
+
+    struct Query;
+
+    impl Debug for Query {
+        fn fmt(&self, fmt: &mut Formatter<'_>) -> Result {
+            fmt.debug_struct("Query")
+                .field("sql", &QUERY)
+                .field("params", &["100", "10"])
+                .finish()
+        }
+    }
+
+    #[test]
+    fn test_unchanged_by_default() {
+        check!(Query);
+    }
+
+    #[test]
+    fn test_truncate() {
+        let opts = PrettyOptions::new().max_string_len(20);
+        assert_snapshot!(pprint_with(Query, &opts));
+    }
+
+    #[test]
+    fn test_truncate_char_boundary() {
+        let opts = PrettyOptions::new().max_string_len(2);
+        assert_eq!(pprint_with("héllo", &opts), r#""h" (5 more bytes)"#);
+    }
+
+    #[test]
+    fn test_truncate_short() {
+        let opts = PrettyOptions::new().max_string_len(20);
+        assert_eq!(pprint_with("short", &opts), r#""short""#);
+    }
+
+    #[test]
+    fn test_wrap() {
+        let opts = PrettyOptions::new().wrap_strings(true);
+        assert_snapshot!(pprint_with(Query, &opts));
+    }
+
+    #[test]
+    fn test_wrap_top_level() {
+        let opts = PrettyOptions::new().width(40).wrap_strings(true);
+        assert_snapshot!(pprint_with(QUERY, &opts));
+    }
+
+    #[test]
+    fn test_wrap_escapes() {
+        let opts = PrettyOptions::new().width(20).wrap_strings(true);
+        assert_snapshot!(pprint_with("line one\nline two\n\"quoted\" text", &opts));
+    }
+
+    #[test]
+    fn test_wrap_roundtrip() {
+        // Remove the escaped newlines, and the whitespace they skip.
+        fn unwrap(s: &str) -> String {
+            let mut lines = s.split("\\\n");
+            let mut out = lines.next().unwrap().to_owned();
+            for line in lines {
+                out.push_str(line.trim_start());
+            }
+            out.replace("\\x20", " ")
+        }
+
+        let s = "lots   of    spaces     between      the       words        here";
+        for width in 5..40 {
+            let opts = PrettyOptions::new().width(width).wrap_strings(true);
+            assert_eq!(unwrap(&pprint_with(s, &opts)), format!("{:?}", s));
+        }
+    }
+
+    #[test]
+    fn test_wrap_and_truncate() {
+        let opts = PrettyOptions::new()
+            .width(40)
+            .wrap_strings(true)
+            .max_string_len(60);
+        assert_snapshot!(pprint_with(QUERY, &opts));
     }
 }
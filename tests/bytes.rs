@@ -0,0 +1,78 @@
+use debug2::{pprint, pprint_with, Bytes, BytesStyle, Debug, PrettyOptions};
+use insta::assert_snapshot;
+
+macro_rules! check {
+    ($e:expr, $style:expr) => {
+        assert_snapshot!(pprint_with($e, &PrettyOptions::new().bytes($style)))
+    };
+}
+
+const LOREM: &[u8] = b"Lorem ipsum dolor sit amet, consectetur adipiscing elit, \
+    sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.\n\0\x01\x02";
+
+#[derive(Debug)]
+struct File {
+    name: String,
+    contents: Vec<u8>,
+}
+
+fn file() -> File {
+    File {
+        name: "lorem.txt".to_owned(),
+        contents: LOREM.to_vec(),
+    }
+}
+
+#[test]
+fn empty() {
+    let opts = |style| PrettyOptions::new().bytes(style);
+    assert_eq!(pprint_with(Vec::<u8>::new(), &opts(BytesStyle::List)), "[]");
+    assert_eq!(
+        pprint_with(Vec::<u8>::new(), &opts(BytesStyle::Escaped)),
+        "b\"\""
+    );
+    assert_eq!(pprint_with(Vec::<u8>::new(), &opts(BytesStyle::Hex)), "[]");
+    assert_eq!(
+        pprint_with(Vec::<u8>::new(), &opts(BytesStyle::HexDump)),
+        "[]"
+    );
+}
+
+#[test]
+fn escaped() {
+    check!(LOREM, BytesStyle::Escaped);
+}
+
+#[test]
+fn escaped_nested() {
+    check!(file(), BytesStyle::Escaped);
+}
+
+#[test]
+fn hex() {
+    check!(LOREM, BytesStyle::Hex);
+}
+
+#[test]
+fn hex_nested() {
+    check!(file(), BytesStyle::Hex);
+}
+
+#[test]
+fn hexdump() {
+    check!(LOREM, BytesStyle::HexDump);
+}
+
+#[test]
+fn hexdump_nested() {
+    check!(vec![file()], BytesStyle::HexDump);
+}
+
+#[test]
+fn wrapper() {
+    assert_eq!(pprint(Bytes(b"\xffquote\"")), r#"b"\xffquote\"""#);
+    assert_eq!(
+        pprint_with(Bytes(b"abc"), &PrettyOptions::new().bytes(BytesStyle::Hex)),
+        "[61 62 63]"
+    );
+}
@@ -0,0 +1,115 @@
+use debug2::{pprint_with, Align, BytesStyle, Debug, PrettyOptions, Theme, TokenKind};
+use insta::assert_snapshot;
+use std::collections::{BTreeMap, HashSet};
+
+#[derive(Debug)]
+struct Config {
+    name: &'static str,
+    enabled: bool,
+    retries: Option<u8>,
+    ratio: f64,
+    servers: Vec<Server>,
+    ports: Vec<u16>,
+    env: BTreeMap<&'static str, &'static str>,
+    tags: HashSet<&'static str>,
+    blob: Vec<u8>,
+}
+
+#[derive(Debug)]
+struct Server {
+    host: &'static str,
+    port: u16,
+}
+
+fn config() -> Config {
+    Config {
+        name: "production",
+        enabled: true,
+        retries: None,
+        ratio: 0.25,
+        servers: vec![
+            Server {
+                host: "alpha.example.com",
+                port: 443,
+            },
+            Server {
+                host: "b.example.com",
+                port: 8080,
+            },
+        ],
+        ports: (8000..8030).collect(),
+        env: vec![("HOME", "/root"), ("SHELL", "/bin/sh")]
+            .into_iter()
+            .collect(),
+        tags: vec!["eu", "primary", "ssd"].into_iter().collect(),
+        blob: b"a string of bytes that is long enough to wrap".to_vec(),
+    }
+}
+
+/// Makes the escape sequences visible.
+fn show(text: &str) -> String {
+    text.replace('\x1b', "\\e")
+}
+
+fn strip(text: &str) -> String {
+    let mut out = String::new();
+    let mut in_escape = false;
+    for c in text.chars() {
+        match c {
+            '\x1b' => in_escape = true,
+            'm' if in_escape => in_escape = false,
+            _ if in_escape => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[test]
+fn default_theme() {
+    let opts = PrettyOptions::new().color(true).sort_unordered(true);
+    let colored = pprint_with(config(), &opts);
+    assert_snapshot!(show(&colored));
+}
+
+#[test]
+fn same_layout() {
+    let variants = vec![
+        PrettyOptions::new(),
+        PrettyOptions::new().width(30),
+        PrettyOptions::new().align(Align::Fields).align_keys(true),
+        PrettyOptions::new().align(Align::Table).wrap_strings(true),
+        PrettyOptions::new()
+            .bytes(BytesStyle::Escaped)
+            .width(40)
+            .max_items(3),
+        PrettyOptions::new().bytes(BytesStyle::Hex).width(50),
+    ];
+    for opts in variants {
+        // The tags are in a `HashSet`, so have to be sorted to be compared.
+        let opts = opts.sort_unordered(true);
+        let plain = pprint_with(config(), &opts);
+        let colored = pprint_with(config(), &opts.theme(every_kind()));
+        assert_ne!(plain, colored);
+        assert_eq!(plain, strip(&colored));
+    }
+}
+
+fn every_kind() -> Theme {
+    Theme::empty()
+        .style(TokenKind::Name, "1")
+        .style(TokenKind::Field, "36")
+        .style(TokenKind::String, "32")
+        .style(TokenKind::Number, "33")
+        .style(TokenKind::Keyword, "35")
+        .style(TokenKind::Punct, "2")
+}
+
+#[test]
+fn custom_theme() {
+    let theme = Theme::new()
+        .style(TokenKind::Name, "")
+        .style(TokenKind::Punct, "2");
+    let colored = pprint_with(vec![Some(1), None], &PrettyOptions::new().theme(theme));
+    assert_snapshot!(show(&colored));
+}
@@ -0,0 +1,190 @@
+use debug2::{assert_eq, dbg, set_dbg_sink, Debug};
+use insta::assert_snapshot;
+use std::env;
+use std::process::Command;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Job {
+    id: u32,
+    name: &'static str,
+    steps: Vec<&'static str>,
+}
+
+fn job() -> Job {
+    Job {
+        id: 7,
+        name: "nightly-build",
+        steps: vec!["checkout", "configure", "compile", "test", "package"],
+    }
+}
+
+/// Runs the test `name` in a new process, returning what it printed to
+/// stderr, with the line numbers hidden.
+fn stderr_of(name: &str) -> String {
+    stderr_with(name, None)
+}
+
+/// Like [`stderr_of`], with `DEBUG2_DBG` set to `filter`.
+fn stderr_with(name: &str, filter: Option<&str>) -> String {
+    let mut command = Command::new(env::current_exe().unwrap());
+    command
+        .args(["--exact", name, "--nocapture", "--test-threads=1"])
+        .env("DEBUG2_DBG_TEST_CHILD", "1")
+        .env_remove("DEBUG2_DBG");
+    if let Some(filter) = filter {
+        command.env("DEBUG2_DBG", filter);
+    }
+    let output = command.output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stderr)
+        .unwrap()
+        .lines()
+        .map(|line| match line.strip_prefix("[tests/dbg.rs:") {
+            Some(rest) => {
+                let end = rest.find(']').unwrap();
+                format!("[tests/dbg.rs:{}{}", "N".repeat(end), &rest[end..])
+            }
+            None => line.to_owned(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn is_child() -> bool {
+    env::var_os("DEBUG2_DBG_TEST_CHILD").is_some()
+}
+
+#[test]
+fn returns() {
+    assert_eq!(dbg!(1 + 1), 2);
+    assert_eq!(dbg!(1, "two",), (1, "two"));
+    assert_eq!(dbg!(job(), 3, None::<u8>), (job(), 3, None));
+
+    // Values are moved through, not copied.
+    let steps = dbg!(job().steps);
+    assert_eq!(steps.len(), 5);
+}
+
+#[test]
+fn print_one() {
+    if is_child() {
+        dbg!();
+        dbg!(job().id);
+        dbg!(job());
+        return;
+    }
+    assert_snapshot!(stderr_of("print_one"));
+}
+
+#[test]
+fn print_many() {
+    if is_child() {
+        let jobs = vec![job(); 2];
+        dbg!(
+            jobs.len(),
+            &jobs,
+            jobs[0].steps.iter().map(|s| s.len()).sum::<usize>()
+        );
+        return;
+    }
+    assert_snapshot!(stderr_of("print_many"));
+}
+
+#[test]
+fn long_expression() {
+    if is_child() {
+        dbg!(job()
+            .steps
+            .into_iter()
+            .filter(|step| step.len() > 4)
+            .collect::<Vec<_>>());
+        return;
+    }
+    assert_snapshot!(stderr_of("long_expression"));
+}
+
+#[test]
+fn sink() {
+    if is_child() {
+        set_dbg_sink(Box::new(|record| {
+            eprintln!(
+                "[{}:{}] column {} in {}: {:?} = {:?}",
+                record.file(),
+                record.line(),
+                record.column(),
+                record.module_path(),
+                record.expr(),
+                record.value(),
+            )
+        }));
+        dbg!();
+        dbg!(job().id, job());
+        return;
+    }
+    assert_snapshot!(stderr_of("sink"));
+}
+
+/// Calls `dbg!` while it's being formatted.
+struct Chatty;
+
+impl Debug for Chatty {
+    fn fmt(&self, f: &mut debug2::Formatter<'_>) -> std::fmt::Result {
+        dbg!("formatting");
+        f.debug_tuple("Chatty").finish()
+    }
+}
+
+#[test]
+fn dbg_while_formatting() {
+    if is_child() {
+        // The value is formatted before the sink is locked, so this doesn't
+        // deadlock.
+        set_dbg_sink(Box::new(|record| {
+            eprintln!("{} = {}", record.expr(), record.value())
+        }));
+        dbg!(Chatty);
+        return;
+    }
+    assert_snapshot!(stderr_of("dbg_while_formatting"));
+}
+
+mod noisy {
+    pub mod inner {
+        pub fn run() {
+            debug2::dbg!("inner");
+        }
+    }
+
+    pub fn run() {
+        debug2::dbg!("noisy");
+        inner::run();
+    }
+}
+
+#[test]
+fn filtered() {
+    if is_child() {
+        dbg!("top");
+        noisy::run();
+        return;
+    }
+    let runs = [
+        None,
+        Some("-dbg::noisy"),
+        Some("dbg::noisy"),
+        Some("dbg::noisy, -dbg::noisy::inner"),
+        Some("-dbg,dbg::noisy::inner"),
+        Some("dbg::nois"),
+    ];
+    let outputs: Vec<_> = runs
+        .iter()
+        .map(|&filter| {
+            format!(
+                "DEBUG2_DBG={:?}\n{}",
+                filter,
+                stderr_with("filtered", filter)
+            )
+        })
+        .collect();
+    assert_snapshot!(outputs.join("\n\n"));
+}
@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+
+use debug2::{pprint_with, Debug, Formatter, PrettyOptions};
+use insta::assert_snapshot;
+
+macro_rules! check {
+    ($e:expr, $max:expr) => {
+        assert_snapshot!(pprint_with($e, &PrettyOptions::new().max_depth($max)))
+    };
+}
+
+#[derive(Debug)]
+enum Tree {
+    Leaf(i32),
+    Node { name: String, children: Vec<Tree> },
+}
+
+fn tree(depth: u32) -> Tree {
+    if depth == 0 {
+        Tree::Leaf(1)
+    } else {
+        Tree::Node {
+            name: format!("level {}", depth),
+            children: vec![Tree::Leaf(2), tree(depth - 1)],
+        }
+    }
+}
+
+#[test]
+fn zero() {
+    check!(tree(3), 0);
+}
+
+#[test]
+fn tree_shallow() {
+    check!(tree(100), 3);
+}
+
+#[test]
+fn tree_deeper() {
+    check!(tree(100), 5);
+}
+
+#[test]
+fn collections() {
+    let mut map = BTreeMap::new();
+    map.insert("empty", vec![]);
+    map.insert("full", vec![(1,), (2,)]);
+    check!(vec![map], 2);
+}
+
+#[test]
+fn unlimited() {
+    check!(tree(2), usize::MAX);
+}
+
+#[test]
+fn builder_in_a_variable() {
+    struct Twice;
+
+    impl Debug for Twice {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            let mut first = f.debug_list();
+            first.entry(&vec![1]);
+            first.finish()?;
+            // The depth is back where it started, so the second list is
+            // shown the same as the first.
+            f.debug_list().entry(&vec![2]).finish()
+        }
+    }
+
+    assert_eq!(
+        pprint_with(Twice, &PrettyOptions::new().max_depth(1)),
+        "[[..]][[..]]"
+    );
+}
+
+#[test]
+fn custom_limit() {
+    struct Short(Vec<i32>);
+
+    impl Debug for Short {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            f.debug_list().limit(3).entries(&self.0).finish()
+        }
+    }
+
+    struct ShortMap(Vec<(i32, i32)>);
+
+    impl Debug for ShortMap {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            f.debug_map()
+                .limit(3)
+                .entries(self.0.iter().map(|(k, v)| (k, v)))
+                .finish()
+        }
+    }
+
+    // A limit doesn't show anything past the depth.
+    let lists = vec![Short(vec![1, 2, 3, 4]), Short(vec![5])];
+    assert_eq!(
+        pprint_with(&lists, &PrettyOptions::new().max_depth(1)),
+        "[[..], [..]]"
+    );
+    let maps = vec![ShortMap(vec![(1, 2)])];
+    assert_eq!(
+        pprint_with(&maps, &PrettyOptions::new().max_depth(1)),
+        "[{..}]"
+    );
+    let value = debug2::to_value(vec![vec![1, 2], vec![3]]);
+    assert_eq!(
+        pprint_with(&value, &PrettyOptions::new().max_depth(1)),
+        "[[..], [..]]"
+    );
+}
@@ -22,3 +22,10 @@ fn simple() {
 
     check!(x);
 }
+
+#[test]
+fn generic() {
+    let x = Wrapper(X { a: 1, b: 5 });
+
+    check!(x);
+}
@@ -0,0 +1,113 @@
+use debug2::{diff, diff_with, Debug, PrettyOptions};
+use insta::assert_snapshot;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone)]
+struct Deployment {
+    name: &'static str,
+    replicas: u32,
+    image: Image,
+    env: BTreeMap<&'static str, &'static str>,
+    ports: Vec<Port>,
+    status: Status,
+}
+
+#[derive(Debug, Clone)]
+struct Image {
+    repo: &'static str,
+    tag: &'static str,
+}
+
+#[derive(Debug, Clone)]
+struct Port {
+    name: &'static str,
+    number: u16,
+}
+
+#[derive(Debug, Clone)]
+enum Status {
+    Running { since: u64 },
+    Failed(String),
+}
+
+fn deployment() -> Deployment {
+    Deployment {
+        name: "web",
+        replicas: 3,
+        image: Image {
+            repo: "registry.example.com/web",
+            tag: "1.4.2",
+        },
+        env: vec![("LOG", "info"), ("MODE", "prod"), ("REGION", "eu")]
+            .into_iter()
+            .collect(),
+        ports: vec![
+            Port {
+                name: "http",
+                number: 80,
+            },
+            Port {
+                name: "https",
+                number: 443,
+            },
+            Port {
+                name: "metrics",
+                number: 9100,
+            },
+        ],
+        status: Status::Running { since: 1_700_000 },
+    }
+}
+
+#[test]
+fn equal() {
+    assert_eq!(diff(deployment(), deployment()), "");
+}
+
+#[test]
+fn nested() {
+    let mut right = deployment();
+    right.image.tag = "1.5.0";
+    right.ports[1].number = 8443;
+    right.env.remove("MODE");
+    right.env.insert("DEBUG", "1");
+    assert_snapshot!(diff(deployment(), right));
+}
+
+#[test]
+fn inserted() {
+    let mut right = deployment();
+    right.ports.insert(
+        1,
+        Port {
+            name: "grpc",
+            number: 9000,
+        },
+    );
+    right.ports.pop();
+    assert_snapshot!(diff(deployment(), right));
+}
+
+#[test]
+fn different_kinds() {
+    let mut right = deployment();
+    right.status = Status::Failed("image pull backoff: registry.example.com/web:1.5.0".into());
+    assert_snapshot!(diff_with(
+        deployment(),
+        right,
+        &PrettyOptions::new().width(40)
+    ));
+}
+
+#[test]
+fn wide() {
+    let left: Vec<Vec<u32>> = vec![(0..30).collect(), (0..5).collect()];
+    let right: Vec<Vec<u32>> = vec![(0..30).map(|n| n * 2).collect(), (0..5).collect()];
+    assert_snapshot!(diff(left, right));
+}
+
+#[test]
+fn top_level() {
+    assert_eq!(diff(1, 2), "-1\n+2");
+    assert_eq!(diff(Some(1), None::<i32>), "-Some(1)\n+None");
+}
@@ -0,0 +1,108 @@
+use debug2::{pprint, pprint_with, Debug, PrettyOptions};
+use std::env;
+use std::process::Command;
+
+#[derive(Debug)]
+struct Tree {
+    name: &'static str,
+    children: Vec<Tree>,
+}
+
+fn tree() -> Tree {
+    let leaf = |name| Tree {
+        name,
+        children: Vec::new(),
+    };
+    Tree {
+        name: "root",
+        children: vec![
+            Tree {
+                name: "branch",
+                children: vec![leaf("a"), leaf("b"), leaf("c")],
+            },
+            leaf("d"),
+        ],
+    }
+}
+
+const VARS: &[&str] = &[
+    "DEBUG2_WIDTH",
+    "DEBUG2_COLOR",
+    "DEBUG2_MAX_DEPTH",
+    "DEBUG2_MAX_ITEMS",
+    "COLUMNS",
+    "NO_COLOR",
+];
+
+/// Runs the `child` test in a new process with only the given variables set,
+/// returning what it printed to stderr.
+fn printed_with(vars: &[(&str, &str)]) -> String {
+    let mut command = Command::new(env::current_exe().unwrap());
+    command
+        .args(["--exact", "child", "--nocapture", "--test-threads=1"])
+        .env("DEBUG2_ENV_TEST_CHILD", "1");
+    for var in VARS {
+        command.env_remove(var);
+    }
+    command.envs(vars.iter().copied());
+    let output = command.output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stderr).unwrap()
+}
+
+#[test]
+fn child() {
+    if env::var_os("DEBUG2_ENV_TEST_CHILD").is_some() {
+        eprint!("{}", pprint(tree()));
+    }
+}
+
+#[test]
+fn unset() {
+    assert_eq!(
+        printed_with(&[]),
+        pprint_with(tree(), &PrettyOptions::new())
+    );
+}
+
+#[test]
+fn width() {
+    let expected = pprint_with(tree(), &PrettyOptions::new().width(200));
+    assert_eq!(printed_with(&[("DEBUG2_WIDTH", "200")]), expected);
+    assert_eq!(
+        printed_with(&[("DEBUG2_WIDTH", "auto"), ("COLUMNS", "200")]),
+        expected
+    );
+}
+
+#[test]
+fn color() {
+    let colored = pprint_with(tree(), &PrettyOptions::new().color(true));
+    let plain = pprint_with(tree(), &PrettyOptions::new());
+    assert_eq!(printed_with(&[("DEBUG2_COLOR", "always")]), colored);
+    assert_eq!(printed_with(&[("DEBUG2_COLOR", "never")]), plain);
+    // Stderr is captured, so isn't a terminal.
+    assert_eq!(printed_with(&[("DEBUG2_COLOR", "auto")]), plain);
+}
+
+#[test]
+fn limits() {
+    let opts = PrettyOptions::new().max_depth(2).max_items(2);
+    assert_eq!(
+        printed_with(&[("DEBUG2_MAX_DEPTH", "2"), ("DEBUG2_MAX_ITEMS", "2")]),
+        pprint_with(tree(), &opts)
+    );
+}
+
+#[test]
+fn not_understood() {
+    assert_eq!(
+        printed_with(&[
+            ("DEBUG2_WIDTH", "wide"),
+            ("DEBUG2_COLOR", "yes"),
+            ("DEBUG2_MAX_DEPTH", "-1"),
+            ("DEBUG2_MAX_ITEMS", ""),
+        ]),
+        pprint_with(tree(), &PrettyOptions::new())
+    );
+}
@@ -0,0 +1,106 @@
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::error::Error;
+use std::fmt;
+
+use debug2::{pprint, ErrorReport};
+
+#[derive(fmt::Debug)]
+struct Outer(Inner);
+
+#[derive(fmt::Debug)]
+struct Inner;
+
+impl fmt::Display for Outer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("failed to load config")
+    }
+}
+
+impl fmt::Display for Inner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("file not found")
+    }
+}
+
+impl Error for Outer {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+impl Error for Inner {}
+
+#[test]
+fn test_error() {
+    let err: Box<dyn Error + Send + Sync> = Box::new(Outer(Inner));
+
+    assert_eq!(
+        pprint(err),
+        "\
+Error {
+    message: \"failed to load config\",
+    source: Error { message: \"file not found\" },
+}"
+    );
+}
+
+#[test]
+fn test_report() {
+    let err = Outer(Inner);
+
+    assert_eq!(
+        pprint(ErrorReport(&err)),
+        pprint(&err as &(dyn Error + 'static))
+    );
+}
+
+#[test]
+fn test_report_nested() {
+    #[derive(fmt::Debug)]
+    struct Wrapped<E>(&'static str, E);
+
+    impl<E> fmt::Display for Wrapped<E> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.0)
+        }
+    }
+
+    impl<E: Error + 'static> Error for Wrapped<E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.1)
+        }
+    }
+
+    let err = Wrapped(
+        "request failed",
+        Wrapped("could not connect to upstream server", Outer(Inner)),
+    );
+
+    assert_eq!(
+        pprint(ErrorReport(&err)),
+        "\
+Error {
+    message: \"request failed\",
+    source: Error {
+        message: \"could not connect to upstream server\",
+        source: Error {
+            message: \"failed to load config\",
+            source: Error { message: \"file not found\" },
+        },
+    },
+}"
+    );
+}
+
+#[test]
+fn test_backtrace() {
+    assert_eq!(pprint(Backtrace::disabled()), "<disabled>");
+
+    let backtrace = Backtrace::force_capture();
+    if backtrace.status() == BacktraceStatus::Captured {
+        let out = pprint(&backtrace);
+        assert!(out.starts_with("Backtrace [\n"));
+        assert!(out.ends_with(",\n]"));
+        assert!(out.contains("test_backtrace"));
+    }
+}
@@ -0,0 +1,59 @@
+use debug2::{pprint, pprint_with, PrettyOptions};
+use insta::assert_snapshot;
+use std::collections::BTreeSet;
+
+#[test]
+fn numbers() {
+    let numbers: Vec<i32> = (0..200).collect();
+    assert_snapshot!(pprint(numbers));
+}
+
+#[test]
+fn bytes() {
+    let bytes: Vec<u8> = (0..=255).step_by(3).collect();
+    assert_snapshot!(pprint(bytes));
+}
+
+#[test]
+fn set() {
+    let set: BTreeSet<u64> = (0..50).map(|n| n * 1_000_003).collect();
+    assert_snapshot!(pprint(set));
+}
+
+#[test]
+fn nested() {
+    let grid: Vec<Vec<u16>> = (0..3)
+        .map(|row| (row * 40..row * 40 + 40).collect())
+        .collect();
+    assert_snapshot!(pprint(grid));
+}
+
+#[test]
+fn not_all_leaves() {
+    // Options aren't leaves, so every entry gets its own line.
+    let mut values: Vec<Option<i32>> = (0..20).map(Some).collect();
+    values.push(None);
+    assert_snapshot!(pprint_with(values, &PrettyOptions::new().width(40)));
+}
+
+#[test]
+fn long_leaf() {
+    let long = "very long ".repeat(10);
+    let words = vec!["short", "words", &long, "after"];
+    assert_snapshot!(pprint_with(words, &PrettyOptions::new().width(40)));
+}
+
+#[test]
+fn with_limit() {
+    let numbers: Vec<i32> = (0..200).collect();
+    assert_snapshot!(pprint_with(numbers, &PrettyOptions::new().max_items(50)));
+}
+
+#[test]
+fn disabled() {
+    let numbers: Vec<i32> = (0..5).collect();
+    assert_snapshot!(pprint_with(
+        numbers,
+        &PrettyOptions::new().width(10).fill(false)
+    ));
+}
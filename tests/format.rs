@@ -0,0 +1,59 @@
+use debug2::{pformat, Debug};
+use insta::assert_snapshot;
+
+#[derive(Debug)]
+struct Request {
+    method: &'static str,
+    path: &'static str,
+    headers: Vec<(&'static str, &'static str)>,
+}
+
+fn request() -> Request {
+    Request {
+        method: "GET",
+        path: "/api/v1/users?page=2",
+        headers: vec![
+            ("accept", "application/json"),
+            ("authorization", "Bearer abc123"),
+        ],
+    }
+}
+
+#[test]
+fn single_line() {
+    assert_eq!(pformat!("no arguments"), "no arguments");
+    assert_eq!(pformat!("{}, {:?}", "a", b'b'), "\"a\", 98");
+    assert_eq!(pformat!("[{:>6}]", Some(1)), "[Some(1)]");
+    assert_eq!(pformat!("[{:>9}]", Some(1)), "[  Some(1)]");
+}
+
+#[test]
+fn captured() {
+    let name = "plain";
+    assert_eq!(pformat!("{name} {}", name), "plain \"plain\"");
+}
+
+#[test]
+fn mid_line() {
+    assert_snapshot!(pformat!(
+        "2024-01-01 INFO handling {} in {}ms",
+        request(),
+        15
+    ));
+}
+
+#[test]
+fn several_lines() {
+    assert_snapshot!(pformat!(
+        "left  = {}\nright = {}\nequal = {}",
+        request(),
+        vec![request().headers; 2],
+        false,
+    ));
+}
+
+#[test]
+fn narrow_column() {
+    let prefix = "x".repeat(66);
+    assert_snapshot!(pformat!("{}{}", prefix, vec![1, 2, 3, 4, 5, 6]));
+}
@@ -0,0 +1,58 @@
+use debug2::{pprint_with, Debug, PrettyOptions};
+use insta::assert_snapshot;
+
+macro_rules! check {
+    ($e:expr, $opts:expr) => {
+        assert_snapshot!(pprint_with($e, &$opts))
+    };
+}
+
+#[derive(Debug)]
+struct Config {
+    name: &'static str,
+    servers: Vec<Server>,
+}
+
+#[derive(Debug)]
+struct Server {
+    host: &'static str,
+    ports: Vec<u16>,
+}
+
+fn config() -> Config {
+    Config {
+        name: "production",
+        servers: vec![
+            Server {
+                host: "alpha.example.com",
+                ports: vec![80, 443, 8080, 8443],
+            },
+            Server {
+                host: "beta.example.com",
+                ports: vec![80, 443],
+            },
+        ],
+    }
+}
+
+#[test]
+fn two_spaces() {
+    check!(config(), PrettyOptions::new().indent_spaces(2));
+}
+
+#[test]
+fn tabs() {
+    check!(config(), PrettyOptions::new().indent("\t"));
+}
+
+#[test]
+fn box_drawing() {
+    check!(config(), PrettyOptions::new().indent("│   "));
+}
+
+#[test]
+fn wide_indent_breaks_earlier() {
+    // The second server fits on a line with 2 space indentation, but not with 8.
+    check!(config(), PrettyOptions::new().width(60).indent_spaces(2));
+    check!(config(), PrettyOptions::new().width(60).indent_spaces(8));
+}
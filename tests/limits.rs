@@ -0,0 +1,93 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use debug2::{pprint_with, Debug, Formatter, PrettyOptions};
+use insta::assert_snapshot;
+
+macro_rules! check {
+    ($e:expr, $max:expr) => {
+        assert_snapshot!(pprint_with($e, &PrettyOptions::new().max_items($max)))
+    };
+}
+
+#[test]
+fn list() {
+    check!(vec![1; 1_000_000], 5);
+}
+
+#[test]
+fn list_pretty() {
+    check!(vec!["Hello World"; 100], 10);
+}
+
+#[test]
+fn nested() {
+    check!(vec![vec![1; 100]; 100], 3);
+}
+
+#[test]
+fn map() {
+    check!((0..1000).map(|i| (i, i * i)).collect::<BTreeMap<_, _>>(), 4);
+}
+
+#[test]
+fn set() {
+    check!((0..1000).collect::<BTreeSet<_>>(), 4);
+}
+
+#[test]
+fn zero() {
+    check!(vec![1, 2, 3], 0);
+}
+
+#[test]
+fn under_limit() {
+    check!(vec![1, 2, 3], 3);
+}
+
+#[test]
+fn inexact_size_hint() {
+    struct Evens;
+
+    impl Debug for Evens {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            f.debug_list()
+                .limit(3)
+                .entries((0..100).filter(|x| x % 2 == 0))
+                .finish()
+        }
+    }
+
+    check!(Evens, usize::MAX);
+}
+
+#[test]
+fn endless() {
+    struct Naturals;
+
+    impl Debug for Naturals {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            f.debug_list().entries((0..).filter(|_| true)).finish()
+        }
+    }
+
+    check!(Naturals, 3);
+}
+
+#[test]
+fn entry_by_entry() {
+    struct Manual;
+
+    impl Debug for Manual {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            f.debug_map()
+                .key(&"a")
+                .value(&1)
+                .key(&"b")
+                .value(&2)
+                .entry(&"c", &3)
+                .finish()
+        }
+    }
+
+    check!(Manual, 2);
+}
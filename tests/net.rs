@@ -0,0 +1,42 @@
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener};
+
+use debug2::{pprint, Debug};
+
+#[test]
+fn test_addrs() {
+    assert_eq!(pprint(Ipv4Addr::new(127, 0, 0, 1)), "127.0.0.1");
+    assert_eq!(pprint(Ipv6Addr::LOCALHOST), "::1");
+
+    let addr: SocketAddr = "10.0.0.1:8080".parse().unwrap();
+    assert_eq!(pprint(addr), "10.0.0.1:8080");
+}
+
+#[test]
+fn test_derived() {
+    #[derive(Debug)]
+    struct Config {
+        bind: SocketAddr,
+        upstream: Vec<Ipv4Addr>,
+    }
+
+    let config = Config {
+        bind: "0.0.0.0:80".parse().unwrap(),
+        upstream: vec![Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::new(10, 0, 0, 2)],
+    };
+
+    assert_eq!(
+        pprint(config),
+        "Config { bind: 0.0.0.0:80, upstream: [10.0.0.1, 10.0.0.2] }"
+    );
+}
+
+#[test]
+fn test_listener() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    assert_eq!(
+        pprint(&listener),
+        format!("TcpListener {{ addr: {} }}", addr)
+    );
+}
@@ -0,0 +1,109 @@
+use debug2::{pprint, pprint_with, Debug, IntStyle, PrettyOptions};
+use insta::assert_snapshot;
+
+#[derive(Debug)]
+struct Sample {
+    small: u8,
+    signed: i16,
+    big: u64,
+    huge: i128,
+    size: usize,
+}
+
+fn sample() -> Sample {
+    Sample {
+        small: 200,
+        signed: -1234,
+        big: 9_876_543_210,
+        huge: -170_141_183_460_469_231_731_687_303_715_884_105_728,
+        size: 0,
+    }
+}
+
+#[test]
+fn styles() {
+    for &style in &[
+        IntStyle::Decimal,
+        IntStyle::Grouped,
+        IntStyle::Hex,
+        IntStyle::Binary,
+        IntStyle::Octal,
+    ] {
+        let opts = PrettyOptions::new().int_style(style);
+        assert_snapshot!(format!("{:?}", style), pprint_with(sample(), &opts));
+    }
+}
+
+#[test]
+fn padded() {
+    for &style in &[IntStyle::Hex, IntStyle::Binary, IntStyle::Octal] {
+        let opts = PrettyOptions::new().int_style(style).pad_ints(true);
+        assert_snapshot!(format!("padded_{:?}", style), pprint_with(sample(), &opts));
+    }
+}
+
+#[derive(Debug)]
+struct Header {
+    #[debug2(hex)]
+    magic: u32,
+    #[debug2(grouped)]
+    length: u64,
+    #[debug2(binary)]
+    flags: u8,
+    #[debug2(hex)]
+    payload: Vec<u8>,
+    checksum: u16,
+}
+
+#[derive(Debug)]
+enum Packet {
+    Data(#[debug2(hex)] u16, Header),
+    Ack {
+        #[debug2(octal)]
+        mode: u32,
+    },
+}
+
+fn header() -> Header {
+    Header {
+        magic: 0xcafe_babe,
+        length: 1_048_576,
+        flags: 0b1010_0101,
+        payload: vec![0xde, 0xad, 0xbe, 0xef],
+        checksum: 4242,
+    }
+}
+
+#[test]
+fn derived() {
+    assert_snapshot!(pprint(header()));
+}
+
+#[test]
+fn derived_enum() {
+    assert_snapshot!(pprint(vec![
+        Packet::Data(0x8001, header()),
+        Packet::Ack { mode: 0o755 },
+    ]));
+}
+
+#[test]
+fn derived_overrides_options() {
+    // `checksum` follows the options, the others keep their own style.
+    let opts = PrettyOptions::new().int_style(IntStyle::Hex).pad_ints(true);
+    assert_snapshot!(pprint_with(header(), &opts));
+}
+
+#[test]
+fn floats() {
+    let values = (1.0f32, 2.0f64 / 3.0, -0.000_123f64, 1e20f64, f64::NAN);
+    assert_snapshot!(pprint(values));
+    assert_snapshot!(pprint_with(
+        values,
+        &PrettyOptions::new().float_precision(3)
+    ));
+    assert_snapshot!(pprint_with(
+        values,
+        &PrettyOptions::new().float_precision(0)
+    ));
+}
@@ -0,0 +1,76 @@
+use std::any::Any;
+
+use debug2::pprint;
+
+#[cfg(feature = "higher-ranked-fn-ptrs")]
+struct Ctx;
+
+#[cfg(feature = "higher-ranked-fn-ptrs")]
+#[derive(debug2::Debug)]
+struct Plugin {
+    name: &'static str,
+    init: fn(&Ctx) -> Result<(), ()>,
+    payload: Box<dyn Any>,
+}
+
+#[cfg(feature = "higher-ranked-fn-ptrs")]
+fn init(_: &Ctx) -> Result<(), ()> {
+    Ok(())
+}
+
+#[cfg(feature = "higher-ranked-fn-ptrs")]
+#[test]
+fn test_plugin() {
+    let plugin = Plugin {
+        name: "foo",
+        init,
+        payload: Box::new(5),
+    };
+
+    let init_ptr = plugin.init as *const ();
+
+    assert_eq!(
+        pprint(&plugin),
+        format!(
+            "Plugin {{ name: \"foo\", init: {:?}, payload: Any {{ .. }} }}",
+            init_ptr
+        )
+    );
+}
+
+#[test]
+fn test_fn_ptrs() {
+    extern "C" fn add(a: i32, b: i32) -> i32 {
+        a + b
+    }
+
+    let ptr: extern "C" fn(i32, i32) -> i32 = add;
+    assert_eq!(pprint(ptr), format!("{:?}", ptr));
+
+    let ptr: unsafe fn(u8) -> bool = is_zero;
+    assert_eq!(pprint(ptr), format!("{:?}", ptr));
+
+    extern "system" fn negate(a: i32) -> i32 {
+        -a
+    }
+
+    let ptr: extern "system" fn(i32) -> i32 = negate;
+    assert_eq!(pprint(ptr), format!("{:?}", ptr));
+}
+
+unsafe fn is_zero(x: u8) -> bool {
+    x == 0
+}
+
+#[cfg(feature = "higher-ranked-fn-ptrs")]
+#[test]
+fn test_borrowed_fn_ptrs() {
+    let ptr: fn(&str, usize) -> String = str::repeat;
+    assert_eq!(pprint(ptr), format!("{:?}", ptr));
+}
+
+#[test]
+fn test_any() {
+    let send: Box<dyn Any + Send + Sync> = Box::new("hello");
+    assert_eq!(pprint(send), "Any { .. }");
+}
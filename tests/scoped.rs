@@ -0,0 +1,46 @@
+use debug2::{pformat, pprint, pprint_checked, with_options, PrettyOptions};
+use std::panic;
+use std::thread;
+
+fn items() -> Vec<u32> {
+    (1..=5).collect()
+}
+
+#[test]
+fn nested() {
+    let two = PrettyOptions::new().max_items(2);
+    let narrow = PrettyOptions::new().width(10);
+
+    let seen = with_options(two, || {
+        let outer = pprint(items());
+        let inner = with_options(narrow, || pprint_checked(items()).unwrap());
+        (outer, inner, pformat!("{:?}", items()))
+    });
+
+    assert_eq!(seen.0, "[1, 2, ... 3 more]");
+    assert_eq!(seen.1, "[\n    1, 2,\n    3, 4,\n    5,\n]");
+    assert_eq!(seen.2, "[1, 2, ... 3 more]");
+    assert_eq!(pprint(items()), "[1, 2, 3, 4, 5]");
+}
+
+#[test]
+fn restored_after_panic() {
+    let result = panic::catch_unwind(|| {
+        with_options(PrettyOptions::new().max_items(0), || {
+            assert_eq!(pprint(items()), "[... 5 more]");
+            panic!("inside with_options");
+        })
+    });
+
+    assert!(result.is_err());
+    assert_eq!(pprint(items()), "[1, 2, 3, 4, 5]");
+}
+
+#[test]
+fn only_this_thread() {
+    with_options(PrettyOptions::new().max_items(0), || {
+        let other = thread::spawn(|| pprint(items())).join().unwrap();
+        assert_eq!(other, "[1, 2, 3, 4, 5]");
+        assert_eq!(pprint(items()), "[... 5 more]");
+    });
+}
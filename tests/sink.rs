@@ -0,0 +1,176 @@
+use debug2::{visit, visit_with, Debug, PrettyOptions, Sink, TokenKind};
+use insta::assert_snapshot;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::net::Ipv4Addr;
+
+/// Writes each event on its own line, indented by how deep it is.
+#[derive(Default)]
+struct Events {
+    out: String,
+    depth: usize,
+}
+
+impl Events {
+    fn line(&mut self, event: String) -> fmt::Result {
+        self.out.push_str(&"  ".repeat(self.depth));
+        self.out.push_str(&event);
+        self.out.push('\n');
+        Ok(())
+    }
+
+    fn begin(&mut self, event: String) -> fmt::Result {
+        self.line(event)?;
+        self.depth += 1;
+        Ok(())
+    }
+}
+
+impl Sink for Events {
+    fn begin_struct(&mut self, name: &str) -> fmt::Result {
+        self.begin(format!("struct {}", name))
+    }
+
+    fn begin_tuple(&mut self, name: &str) -> fmt::Result {
+        self.begin(format!("tuple {:?}", name))
+    }
+
+    fn begin_list(&mut self) -> fmt::Result {
+        self.begin("list".to_owned())
+    }
+
+    fn begin_set(&mut self) -> fmt::Result {
+        self.begin("set".to_owned())
+    }
+
+    fn begin_map(&mut self) -> fmt::Result {
+        self.begin("map".to_owned())
+    }
+
+    fn field(&mut self, name: &str) -> fmt::Result {
+        self.line(format!("field {}", name))
+    }
+
+    fn scalar(&mut self, kind: TokenKind, text: &str) -> fmt::Result {
+        self.line(format!("{:?} {}", kind, text))
+    }
+
+    fn non_exhaustive(&mut self) -> fmt::Result {
+        self.line("..".to_owned())
+    }
+
+    fn more(&mut self, count: usize, at_least: bool) -> fmt::Result {
+        let plus = if at_least { "+" } else { "" };
+        self.line(format!("{}{} more", count, plus))
+    }
+
+    fn end(&mut self) -> fmt::Result {
+        self.depth -= 1;
+        self.line("end".to_owned())
+    }
+}
+
+fn events<T: Debug>(x: T) -> String {
+    let mut sink = Events::default();
+    visit(x, &mut sink).unwrap();
+    sink.out
+}
+
+fn events_with<T: Debug>(x: T, opts: &PrettyOptions) -> String {
+    let mut sink = Events::default();
+    visit_with(x, opts, &mut sink).unwrap();
+    sink.out
+}
+
+#[derive(Debug)]
+struct Host {
+    name: String,
+    addr: Ipv4Addr,
+    shape: Shape,
+    up: bool,
+    unit: Unit,
+}
+
+#[derive(Debug)]
+enum Shape {
+    Circle(f64),
+    Rect { w: u8, h: u8 },
+}
+
+#[derive(Debug)]
+struct Unit;
+
+#[test]
+fn derived() {
+    let host = Host {
+        name: "web".to_owned(),
+        addr: Ipv4Addr::LOCALHOST,
+        shape: Shape::Rect { w: 3, h: 4 },
+        up: true,
+        unit: Unit,
+    };
+    assert_snapshot!(events((host, Shape::Circle(1.5), None::<()>, ())));
+}
+
+#[test]
+fn collections() {
+    let map: BTreeMap<_, _> = vec![("a", vec![1, 2]), ("b", vec![])].into_iter().collect();
+    let set: std::collections::HashSet<_> = vec![3, 1, 2].into_iter().collect();
+    let opts = PrettyOptions::new().sort_unordered(true);
+    assert_snapshot!(events_with((map, set), &opts));
+}
+
+struct Secret;
+
+impl Debug for Secret {
+    fn fmt(&self, f: &mut debug2::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Secret")
+            .field("id", &7)
+            .finish_non_exhaustive()
+    }
+}
+
+#[test]
+fn left_out() {
+    let opts = PrettyOptions::new()
+        .max_items(2)
+        .max_depth(2)
+        .max_string_len(3);
+    let value = (vec![1, 2, 3, 4], vec![vec![1]], "truncated", Secret);
+    assert_snapshot!(events_with(value, &opts));
+}
+
+#[test]
+fn errors() {
+    struct Stop;
+
+    impl Sink for Stop {
+        fn begin_struct(&mut self, _: &str) -> fmt::Result {
+            Ok(())
+        }
+        fn begin_tuple(&mut self, _: &str) -> fmt::Result {
+            Ok(())
+        }
+        fn begin_list(&mut self) -> fmt::Result {
+            Err(fmt::Error)
+        }
+        fn begin_set(&mut self) -> fmt::Result {
+            Ok(())
+        }
+        fn begin_map(&mut self) -> fmt::Result {
+            Ok(())
+        }
+        fn field(&mut self, _: &str) -> fmt::Result {
+            Ok(())
+        }
+        fn scalar(&mut self, _: TokenKind, _: &str) -> fmt::Result {
+            Ok(())
+        }
+        fn end(&mut self) -> fmt::Result {
+            Ok(())
+        }
+    }
+
+    assert!(visit(Some(1), &mut Stop).is_ok());
+    assert!(visit(Some(vec![1]), &mut Stop).is_err());
+}
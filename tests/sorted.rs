@@ -0,0 +1,102 @@
+use debug2::{pprint, pprint_with, Debug, Formatter, PrettyOptions};
+use insta::assert_snapshot;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct Version {
+    major: u32,
+    minor: u32,
+}
+
+fn sorted() -> PrettyOptions {
+    PrettyOptions::new().sort_unordered(true)
+}
+
+#[test]
+fn hash_map() {
+    let map: HashMap<String, usize> = ["zeta", "alpha", "item2", "item10", "item1", "Beta"]
+        .iter()
+        .map(|s| (s.to_string(), s.len()))
+        .collect();
+    assert_snapshot!(pprint_with(map, &sorted()));
+}
+
+#[test]
+fn hash_set() {
+    let set: HashSet<i64> = (0..40).map(|n| n * n * 37 % 1000).collect();
+    assert_snapshot!(pprint_with(set, &sorted()));
+}
+
+#[test]
+fn struct_keys() {
+    let set: HashSet<Version> = [(1, 10), (1, 2), (0, 9), (10, 0), (2, 0)]
+        .iter()
+        .map(|&(major, minor)| Version { major, minor })
+        .collect();
+    assert_snapshot!(pprint_with(set, &sorted()));
+}
+
+#[test]
+fn nested() {
+    let mut map: HashMap<&str, HashSet<u8>> = HashMap::new();
+    map.insert("odd", (1..20).step_by(2).collect());
+    map.insert("even", (0..20).step_by(2).collect());
+    map.insert("empty", HashSet::new());
+    assert_snapshot!(pprint_with(map, &sorted()));
+}
+
+#[test]
+fn negative_keys() {
+    let map: HashMap<i32, i32> = [-10, -5, -1, 0, 3, 12, -100]
+        .iter()
+        .map(|&n| (n, n * n))
+        .collect();
+    assert_snapshot!(pprint_with(map, &sorted()));
+
+    let set: HashSet<(i8, &str)> = [(-2, "item-10"), (-2, "item-9"), (-12, "a"), (1, "b")]
+        .iter()
+        .copied()
+        .collect();
+    assert_snapshot!(pprint_with(set, &sorted()));
+}
+
+#[test]
+fn with_limit() {
+    // The smallest entries are shown, whatever order they're stored in.
+    let set: HashSet<u32> = (0..1000).collect();
+    assert_snapshot!(pprint_with(set, &sorted().max_items(5)));
+}
+
+#[test]
+fn map_with_limit() {
+    let map: HashMap<u32, u32> = (0..20).map(|n| (n, n)).collect();
+    assert_snapshot!(pprint_with(map, &sorted().max_items(3)));
+}
+
+struct Endless;
+
+impl Debug for Endless {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_set().sorted().limit(3).entries(0..).finish()
+    }
+}
+
+#[test]
+fn endless() {
+    assert_snapshot!(pprint(Endless));
+}
+
+#[test]
+fn unsorted() {
+    let set: HashSet<u32> = (0..100).collect();
+    let text = pprint_with(&set, &PrettyOptions::new());
+    let mut shown: Vec<u32> = text
+        .trim_matches(|c| c == '{' || c == '}' || char::is_whitespace(c))
+        .split(',')
+        .filter(|n| !n.trim().is_empty())
+        .map(|n| n.trim().parse().unwrap())
+        .collect();
+    shown.sort_unstable();
+    assert_eq!(shown, (0..100).collect::<Vec<_>>());
+}
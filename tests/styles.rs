@@ -0,0 +1,92 @@
+use debug2::{pprint_with, Debug, Formatter, PrettyOptions};
+use insta::assert_snapshot;
+use std::collections::BTreeMap;
+use std::fmt;
+
+macro_rules! check {
+    ($e:expr, $opts:expr) => {
+        assert_snapshot!(pprint_with($e, &$opts))
+    };
+}
+
+#[derive(Debug)]
+struct Config {
+    name: &'static str,
+    ports: Vec<u16>,
+    env: BTreeMap<&'static str, &'static str>,
+    pair: (u8, bool),
+    single: (i32,),
+}
+
+fn config() -> Config {
+    Config {
+        name: "production",
+        ports: vec![80, 443, 8080, 8443],
+        env: vec![("HOME", "/root"), ("SHELL", "/bin/sh")]
+            .into_iter()
+            .collect(),
+        pair: (1, true),
+        single: (5,),
+    }
+}
+
+struct Hidden(u8);
+
+impl Debug for Hidden {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Hidden")
+            .field("shown", &self.0)
+            .finish_non_exhaustive()
+    }
+}
+
+#[test]
+fn no_trailing_comma() {
+    check!(
+        config(),
+        PrettyOptions::new().width(20).trailing_comma(false)
+    );
+}
+
+#[test]
+fn no_trailing_comma_after_marker() {
+    check!(
+        vec![vec![1, 2, 3, 4, 5, 6]],
+        PrettyOptions::new()
+            .width(10)
+            .max_items(3)
+            .trailing_comma(false)
+    );
+}
+
+#[test]
+fn no_space_inside_braces() {
+    check!(
+        config(),
+        PrettyOptions::new().width(200).space_inside_braces(false)
+    );
+    check!(
+        vec![Hidden(1)],
+        PrettyOptions::new().space_inside_braces(false)
+    );
+}
+
+#[test]
+fn equals_separator() {
+    check!(
+        config(),
+        PrettyOptions::new().width(200).field_separator(" = ")
+    );
+    check!(
+        config(),
+        PrettyOptions::new().width(20).field_separator(" = ")
+    );
+}
+
+#[test]
+fn non_exhaustive_pretty() {
+    check!(
+        vec![Hidden(1), Hidden(2)],
+        PrettyOptions::new().width(10).trailing_comma(false)
+    );
+}
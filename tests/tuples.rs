@@ -0,0 +1,58 @@
+use debug2::{pprint, Debug, Formatter};
+use insta::assert_snapshot;
+use std::fmt;
+
+macro_rules! check {
+    ($e:expr) => {
+        assert_snapshot!(pprint($e))
+    };
+}
+
+#[test]
+fn unit() {
+    check!(());
+}
+
+#[test]
+fn unnamed_builder() {
+    // Like std, an empty unnamed tuple builder writes nothing; only the unit
+    // type writes `()`.
+    struct Nothing;
+
+    impl Debug for Nothing {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.debug_tuple("").finish()
+        }
+    }
+
+    assert_eq!(pprint(Nothing), "");
+    assert_eq!(pprint(((), Some(()))), "((), Some(()))");
+}
+
+#[test]
+fn single() {
+    check!((1,));
+}
+
+#[test]
+fn single_nested() {
+    check!(((1,), ("a",)));
+}
+
+#[test]
+fn single_long() {
+    check!((vec!["Hello World"; 6],));
+}
+
+#[test]
+fn arity_16() {
+    check!((0u8, 1u16, 2u32, 3u64, 4i8, 5i16, 6i32, 7i64, 8, 9, 10, 11, 12, 13, 14, 15));
+}
+
+#[test]
+fn arity_16_long() {
+    check!((
+        "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+        "eleven", "twelve", "thirteen", "fourteen", "fifteen",
+    ));
+}
@@ -0,0 +1,148 @@
+use debug2::{
+    pprint, pprint_with, to_value, Align, Debug, Formatter, PrettyOptions, TokenKind, Value,
+};
+use insta::assert_snapshot;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::net::Ipv4Addr;
+
+#[derive(Debug)]
+struct Server {
+    name: String,
+    addr: Ipv4Addr,
+    ports: Vec<u16>,
+    tags: HashSet<&'static str>,
+    env: HashMap<String, Option<String>>,
+    kind: Kind,
+}
+
+#[derive(Debug)]
+enum Kind {
+    Primary,
+    Replica(usize, bool),
+    Proxy { upstream: &'static str },
+}
+
+fn servers() -> Vec<Server> {
+    let kinds = vec![
+        Kind::Primary,
+        Kind::Replica(1, true),
+        Kind::Proxy {
+            upstream: "primary.internal",
+        },
+    ];
+    kinds
+        .into_iter()
+        .enumerate()
+        .map(|(i, kind)| Server {
+            name: format!("server-{}", i),
+            addr: Ipv4Addr::new(10, 0, 0, i as u8),
+            ports: (0..i as u16 * 12).map(|p| 8000 + p).collect(),
+            tags: vec!["eu", "ssd"].into_iter().take(i).collect(),
+            env: vec![("HOME".to_owned(), Some("/root".to_owned()))]
+                .into_iter()
+                .chain((0..i).map(|n| (format!("VAR_{}", n), None)))
+                .collect(),
+            kind,
+        })
+        .collect()
+}
+
+struct Limited(Vec<u8>);
+
+impl Debug for Limited {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().limit(3).entries(&self.0).finish()
+    }
+}
+
+struct Secret;
+
+impl Debug for Secret {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Secret")
+            .field("id", &1)
+            .finish_non_exhaustive()
+    }
+}
+
+#[test]
+fn same_output() {
+    let options = vec![
+        PrettyOptions::new(),
+        PrettyOptions::new().width(40),
+        PrettyOptions::new().width(20).fill(false),
+        PrettyOptions::new().align(Align::Table).align_keys(true),
+        PrettyOptions::new().max_items(2).max_depth(3),
+        PrettyOptions::new().color(true),
+    ];
+    // The same servers are used for both, so their `HashSet`s and `HashMap`s
+    // are in the same order.
+    let servers = servers();
+    let value = to_value(&servers);
+    for opts in options {
+        assert_eq!(pprint_with(&value, &opts), pprint_with(&servers, &opts));
+    }
+}
+
+#[test]
+fn left_out() {
+    let original = (Limited((0..10).collect()), Secret, ((), (1,)));
+    assert_eq!(pprint(to_value(&original)), pprint(&original));
+}
+
+struct Endless;
+
+impl Debug for Endless {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .limit(3)
+            .entries((0..).filter(|_| true))
+            .finish()
+    }
+}
+
+#[test]
+fn uncounted() {
+    let value = to_value(Endless);
+    assert_eq!(pprint(&value), pprint(Endless));
+    assert_eq!(pprint(&value), "[0, 1, 2, ... 10_000+ more]");
+}
+
+#[test]
+fn elided_round_trip() {
+    // Recording a `Value` gives the same `Value` back.
+    let elided = |value| Value::NonExhaustive {
+        value: Box::new(value),
+        more: None,
+        at_least: false,
+    };
+    for value in [
+        elided(Value::List(Vec::new())),
+        elided(Value::Set(Vec::new())),
+        elided(Value::Map(Vec::new())),
+    ] {
+        assert_eq!(to_value(&value), value);
+    }
+}
+
+#[test]
+fn structure() {
+    let mut map = BTreeMap::new();
+    map.insert("a", vec![Some(1.5)]);
+    let value = to_value((map, Kind::Replica(2, false), Limited(vec![1; 5]), "hi"));
+    assert_snapshot!(format!("{:#?}", value));
+}
+
+#[test]
+fn atoms() {
+    assert_eq!(to_value(1), Value::Atom(TokenKind::Number, "1".to_owned()));
+    assert_eq!(
+        to_value("a\nb"),
+        Value::Atom(TokenKind::String, "\"a\\nb\"".to_owned())
+    );
+    assert_eq!(
+        to_value(None::<u8>),
+        Value::Atom(TokenKind::Keyword, "None".to_owned())
+    );
+}